@@ -48,6 +48,56 @@ pub trait BoolExt {
   {
     if self.into() { Ok(()) } else { Err(err) }
   }
+
+  /// The inverse of [then_ok_or_else](Self::then_ok_or_else): `false` is the
+  /// success case.
+  ///
+  /// If the value is `false`, it returns `Ok(())`. If the value is `true`, it
+  /// returns `Err(err())`.
+  ///
+  /// Useful when the condition describes a failure, e.g. `is_dirty`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use testutils::bool_ext::BoolExt;
+  ///
+  /// assert_eq!(true.not_then_ok_or_else(|| "dirty"), Err("dirty"));
+  /// assert_eq!(false.not_then_ok_or_else(|| "dirty"), Ok(()));
+  /// ```
+  fn not_then_ok_or_else<E>(self, err_fn: impl FnOnce() -> E) -> Result<(), E>
+  where
+    Self: Into<bool>,
+  {
+    if self.into() { Err(err_fn()) } else { Ok(()) }
+  }
+
+  /// Like [then_ok_or_else](Self::then_ok_or_else), but carries a success
+  /// payload.
+  ///
+  /// If the value is `true`, it returns `Ok(ok())`. If the value is `false`,
+  /// it returns `Err(err())`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use testutils::bool_ext::BoolExt;
+  ///
+  /// let res: Result<u8, &str> = true.and_then_ok(|| 42, || "error");
+  /// assert_eq!(res, Ok(42));
+  ///
+  /// assert_eq!(false.and_then_ok(|| 42, || "error"), Err("error"));
+  /// ```
+  fn and_then_ok<T, E>(
+    self,
+    ok_fn: impl FnOnce() -> T,
+    err_fn: impl FnOnce() -> E,
+  ) -> Result<T, E>
+  where
+    Self: Into<bool>,
+  {
+    if self.into() { Ok(ok_fn()) } else { Err(err_fn()) }
+  }
 }
 
 impl BoolExt for bool {}
@@ -63,7 +113,9 @@ mod tests {
   fn test_tap_opt() {
     let opt = Some(42);
     opt.tap_some(|&x| assert_eq!(x, 42));
-    opt.map(|x| assert_eq!(x, 42));
+    if let Some(x) = opt {
+      assert_eq!(x, 42)
+    }
   }
 
   #[ignore]
@@ -77,6 +129,22 @@ mod tests {
     assert_eq!(value.then_ok_or_else(|| "error"), Err("error"));
   }
 
+  struct Flag(bool);
+
+  impl From<Flag> for bool {
+    fn from(value: Flag) -> Self {
+      value.0
+    }
+  }
+
+  impl BoolExt for Flag {}
+
+  #[test]
+  fn test_and_then_ok_custom_bool() {
+    assert_eq!(Flag(true).and_then_ok(|| "payload", || ()), Ok("payload"));
+    assert_eq!(Flag(false).and_then_ok(|| "payload", || 1), Err(1));
+  }
+
   #[cfg(feature = "std")]
   #[ignore]
   #[test]
@@ -1,4 +1,4 @@
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 /*!
@@ -20,10 +20,31 @@
   Configurable command builders:
   - Preconfigured cargo command structs (e.g., `CargoDoc`, `CargoCmd`)
   - Cross-platform command execution utilities
+
+- **presets**
+  - Only the argv builders of `os_cmd::presets` (e.g., `CargoCmd::into_vec()`).
+  - Requires `alloc` only, so it can be used in `no_std`. Execution still
+    requires `os_cmd`.
+
+- **logger**
+  - Provides [init_debug_logger()] (via `env_logger`).
+
+- **tokio**
+  - Async variants of `os_cmd::CommandSpawner` (e.g., `spawn_async()`), via
+    `tokio::process`. The sync API stays the default.
+
+- **serde**
+  - `Serialize`/`Deserialize` for the cargo presets (e.g., `CargoCmd`,
+    `CargoDoc`, `RustFlags`), e.g., to load them from TOML/JSON fixtures.
+    String-like enums use their `as_str()` form (e.g., `"thin"`).
+
+- **json**
+  - Enables `serde` and `RunnableCommand::to_json_args()`, which exports the
+    argv as a JSON array.
 */
 extern crate alloc;
 
-#[cfg(feature = "os_cmd")]
+#[cfg(feature = "presets")]
 pub mod os_cmd;
 
 mod macros;
@@ -43,20 +64,161 @@ pub use tap;
 /// Runs the given function and prints the elapsed time.
 /// It supports stable Rust.
 ///
+/// Returns the closure's result together with the elapsed time.
+///
 /// ## Example
 ///
-/// ```ignore
-/// fn bench_foo() {
-///   testutils::simple_benchmark(|| {
-///     foo() // Your code here...
-///   })
-/// }
 /// ```
+/// let v = (1..=100).collect::<Vec<u32>>();
+///
+/// let (sum, elapsed) = testutils::simple_benchmark(|| v.iter().sum::<u32>());
+///
+/// assert_eq!(sum, 5050);
+/// assert!(!elapsed.is_zero());
+/// ```
+#[cfg(feature = "std")]
+pub fn simple_benchmark<U, F: FnOnce() -> U>(f: F) -> (U, std::time::Duration) {
+  let (value, elapsed) = simple_benchmark_quiet(f);
+  eprintln!("Time taken: {elapsed:?}");
+
+  (value, elapsed)
+}
+
+/// Same as [simple_benchmark], but doesn't print the elapsed time.
 #[cfg(feature = "std")]
-pub fn simple_benchmark<U, F: FnOnce() -> U>(f: F) {
+pub fn simple_benchmark_quiet<U, F: FnOnce() -> U>(
+  f: F,
+) -> (U, std::time::Duration) {
   let start = std::time::Instant::now();
-  f();
-  let elapsed = start.elapsed();
+  let value = f();
+
+  (value, start.elapsed())
+}
+
+/// Initializes `env_logger` at `Debug` level, at most once per process.
+///
+/// It is safe to call from every test: subsequent calls (or a logger that has
+/// already been installed elsewhere) are silently ignored.
+///
+/// ## Example
+///
+/// ```
+/// testutils::init_debug_logger();
+/// testutils::init_debug_logger(); // no-op
+///
+/// log::debug!("visible with `cargo test -- --nocapture`");
+/// ```
+#[cfg(feature = "logger")]
+pub fn init_debug_logger() {
+  new_once_lock!(INIT: ());
+
+  INIT.get_or_init(|| {
+    // Another logger may already be set; that's fine.
+    let _ = env_logger::builder()
+      .filter_level(log::LevelFilter::Debug)
+      .is_test(true)
+      .try_init();
+  });
+}
+
+/// Timing statistics collected by [bench_iters].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchStats {
+  pub min: std::time::Duration,
+  pub mean: std::time::Duration,
+  pub max: std::time::Duration,
+  pub total: std::time::Duration,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for BenchStats {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let Self {
+      min,
+      mean,
+      max,
+      total,
+    } = self;
+    write!(
+      f,
+      "min: {min:?}, mean: {mean:?}, max: {max:?}, total: {total:?}"
+    )
+  }
+}
+
+/// Runs the given function `iters` times and returns the timing statistics.
+///
+/// ## Panics
+///
+/// Panics if `iters` is 0.
+///
+/// ## Example
+///
+/// ```
+/// let stats = testutils::bench_iters(100, || {
+///   core::hint::black_box((1..=100u32).sum::<u32>());
+/// });
+///
+/// assert!(stats.min <= stats.mean && stats.mean <= stats.max);
+/// eprintln!("{stats}");
+/// ```
+#[cfg(feature = "std")]
+pub fn bench_iters<F: FnMut()>(iters: usize, mut f: F) -> BenchStats {
+  use std::time::{Duration, Instant};
+
+  assert!(iters > 0, "bench_iters: `iters` must be greater than 0");
+
+  let (min, max, total) = (0..iters)
+    .map(|_| {
+      let start = Instant::now();
+      f();
+      start.elapsed()
+    })
+    .fold(
+      (Duration::MAX, Duration::ZERO, Duration::ZERO),
+      |(min, max, total), d| (min.min(d), max.max(d), total + d),
+    );
+
+  let mean = (total.as_nanos() / iters as u128)
+    .try_into()
+    .map(Duration::from_nanos)
+    .unwrap_or(Duration::MAX);
+
+  BenchStats {
+    min,
+    mean,
+    max,
+    total,
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bench_iters_stats() {
+    let mut n = 0u64;
+    let stats = bench_iters(1000, || n = core::hint::black_box(n + 1));
+
+    assert_eq!(n, 1000);
+    assert!(stats.min <= stats.mean);
+    assert!(stats.mean <= stats.max);
+    assert!(stats.max <= stats.total);
+  }
+
+  #[test]
+  #[cfg(feature = "logger")]
+  fn init_debug_logger_twice() {
+    init_debug_logger();
+    init_debug_logger();
+    log::debug!("logger initialized");
+  }
 
-  eprintln!("Time taken: {elapsed:?}")
+  #[test]
+  #[should_panic(expected = "must be greater than 0")]
+  fn bench_zero_iters() {
+    bench_iters(0, || {});
+  }
 }
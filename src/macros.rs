@@ -31,6 +31,47 @@ macro_rules! generate_struct_arr {
     ]
   }};
 }
+
+/// Like [generate_struct_arr!], but yields references to the fields:
+/// `[(stringify!(field), &self.field), ...]`.
+///
+/// Useful for fields that are not `Copy` (e.g., `Option<MiniStr>`).
+///
+/// ## Example
+///
+/// ```
+/// use testutils::struct_field_refs;
+///
+/// struct Linker<'a> {
+///   linker: Option<&'a str>,
+///   flavor: Option<&'a str>,
+/// }
+///
+/// let l = Linker {
+///   linker: Some("rust-lld"),
+///   flavor: None,
+/// };
+///
+/// let arr = struct_field_refs![ l => linker, flavor ];
+/// assert_eq!(
+///   arr,
+///   [("linker", &Some("rust-lld")), ("flavor", &None)]
+/// );
+///
+/// let set = arr
+///   .iter()
+///   .filter_map(|(name, v)| v.map(|_| *name))
+///   .collect::<Vec<_>>();
+/// assert_eq!(set, ["linker"]);
+/// ```
+#[macro_export]
+macro_rules! struct_field_refs {
+  ($self:ident => $( $field:ident ),* $(,)? ) => {{
+    [
+      $( ( stringify!($field), &$self.$field ), )*
+    ]
+  }};
+}
 /// Generates a **static** `OnceLock` variable with the given name and type.
 ///
 /// ## Example
@@ -53,3 +94,76 @@ macro_rules! new_once_lock {
     static $name: ::std::sync::OnceLock<$t> = ::std::sync::OnceLock::new();
   };
 }
+
+/// Evaluates an expression, prints the elapsed time to stderr, and returns the
+/// expression's value.
+///
+/// This is the expression form of
+/// [simple_benchmark](crate::simple_benchmark), without the closure.
+///
+/// The stderr format is `{source text}: {elapsed:?}`, e.g.,
+/// `expensive(): 1.2ms`.
+///
+/// The value is returned as-is, so it composes with `?`: `time!(f())?` times
+/// `f()` and then propagates its error.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::time;
+///
+/// fn expensive() -> u64 {
+///   (1..=1_000u64).sum()
+/// }
+///
+/// let v = time!(expensive()); // stderr: expensive(): 1.2ms
+/// assert_eq!(v, 500500);
+///
+/// fn parse() -> Result<u8, core::num::ParseIntError> {
+///   let n = time!("42".parse::<u8>())?;
+///   Ok(n)
+/// }
+/// assert_eq!(parse(), Ok(42));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! time {
+  ($val:expr $(,)?) => {{
+    let start = ::std::time::Instant::now();
+    let value = $val;
+    ::std::eprintln!("{}: {:?}", stringify!($val), start.elapsed());
+    value
+  }};
+}
+
+/// Like [time!](crate::time!), but reports the elapsed time via
+/// `log::debug!` instead of stderr.
+///
+/// The log format is `{source text}: {elapsed:?}`.
+///
+/// Requires an initialized logger (e.g.,
+/// [init_debug_logger()](crate::init_debug_logger) with the `logger`
+/// feature); otherwise, nothing is emitted. Like [dbg_ref!](crate::dbg_ref!),
+/// the calling crate must depend on `log`.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::time_dbg;
+///
+/// # #[cfg(feature = "logger")]
+/// testutils::init_debug_logger();
+///
+/// let v = time_dbg!((1..=100u32).sum::<u32>()); // debug: (1..=100u32).sum::<u32>(): 1.2µs
+/// assert_eq!(v, 5050);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! time_dbg {
+  ($val:expr $(,)?) => {{
+    let start = ::std::time::Instant::now();
+    let value = $val;
+    log::debug!("{}: {:?}", stringify!($val), start.elapsed());
+    value
+  }};
+}
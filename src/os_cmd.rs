@@ -1,27 +1,55 @@
 mod collect;
 pub use collect::collect_boxed_ministr_slice;
+#[cfg(feature = "os_cmd")]
+pub use collect::join_quoted;
 
 /// Provides configurable command runners such as `CargoDoc` and `CargoCmd`.
+///
+/// With only the `presets` feature (no `std`), the argv builders (e.g.,
+/// `into_vec()`) are still available.
 pub mod presets;
 
-mod repr;
-use alloc::borrow::Cow;
-use std::ffi::{OsStr, OsString};
-
 pub use compact_str::{CompactString as MiniStr, format_compact as fmt_compact};
-pub use repr::{CommandRepr, collect_raw, remove_comments_and_collect};
 
+#[cfg(feature = "os_cmd")]
+mod repr;
+#[cfg(feature = "os_cmd")]
+pub use repr::{
+  CommandRepr, collect_raw, collect_raw_with_continuations,
+  remove_comments_and_collect, strip_comment_lines,
+};
+
+#[cfg(feature = "os_cmd")]
 mod runner;
+#[cfg(feature = "os_cmd")]
 pub use runner::{RunnableCommand, Runner, RunnerInspection};
 
+#[cfg(feature = "os_cmd")]
 mod process;
+#[cfg(feature = "os_cmd")]
 pub use process::{CommandSpawner, CowOsStrVec, StdioMode, run_os_cmd as run};
 
+#[cfg(feature = "os_cmd")]
+mod spawn_guard;
+#[cfg(feature = "os_cmd")]
+pub use spawn_guard::SpawnGuard;
+
+#[cfg(feature = "os_cmd")]
+mod error;
+#[cfg(feature = "os_cmd")]
+pub use error::OsCmdError;
+
+#[cfg(feature = "os_cmd")]
 mod decoded;
+#[cfg(feature = "os_cmd")]
 pub use decoded::DecodedText;
 
-pub fn cow_str_into_cow_osstr(s: Cow<'_, str>) -> Cow<'_, OsStr> {
-  use Cow::{Borrowed, Owned};
+#[cfg(feature = "os_cmd")]
+pub fn cow_str_into_cow_osstr(
+  s: alloc::borrow::Cow<'_, str>,
+) -> alloc::borrow::Cow<'_, std::ffi::OsStr> {
+  use alloc::borrow::Cow::{Borrowed, Owned};
+  use std::ffi::{OsStr, OsString};
 
   match s {
     Borrowed(b) => OsStr::new(b).into(),
@@ -29,7 +57,7 @@ pub fn cow_str_into_cow_osstr(s: Cow<'_, str>) -> Cow<'_, OsStr> {
   }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "os_cmd"))]
 mod tests {
   use super::*;
 
@@ -39,7 +67,7 @@ mod tests {
     let runner =
       Runner::default().with_command(repr::CommandRepr::Raw("cargo +nightly fmt"));
     // assert_eq!(runner.get_command(), CommandRepr::Raw("cargo +nightly fmt"));
-    assert_eq!(*runner.get_remove_comments(), true);
+    assert!(*runner.get_remove_comments());
     // runner.set_raw("cargo fmt");
     // runner.set_trim(false);
     // runner.set_remove_comments(true);
@@ -1,3 +1,5 @@
+use alloc::boxed::Box;
+
 use crate::os_cmd::MiniStr;
 
 /// iter => `Box<[MiniStr]>`
@@ -26,6 +28,39 @@ where
     .collect()
 }
 
+/// Shell-quotes a single arg via [shlex::try_quote].
+///
+/// An arg that cannot be quoted (it contains a nul byte) falls back to its
+/// `Debug` form.
+#[cfg(feature = "os_cmd")]
+pub(crate) fn quote_arg(arg: &str) -> alloc::borrow::Cow<'_, str> {
+  shlex::try_quote(arg).unwrap_or_else(|_| alloc::format!("{arg:?}").into())
+}
+
+/// Shell-quotes each arg (spaces, quotes, `$`, etc.) and joins them with a
+/// single space, so the result can be pasted into a shell.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::{MiniStr, join_quoted};
+///
+/// let args = ["echo", "hello world", "$HOME"].map(MiniStr::from);
+/// assert_eq!(join_quoted(&args), "echo 'hello world' '$HOME'");
+/// ```
+#[cfg(feature = "os_cmd")]
+pub fn join_quoted(args: &[MiniStr]) -> MiniStr {
+  let mut joined = MiniStr::default();
+
+  for (i, arg) in args.iter().enumerate() {
+    if i != 0 {
+      joined.push(' ')
+    }
+    joined.push_str(&quote_arg(arg))
+  }
+  joined
+}
+
 #[cfg(test)]
 mod tests {
   use tap::Pipe;
@@ -42,4 +77,16 @@ mod tests {
     assert_eq!(slice[1], "+nightly");
     assert_eq!(slice[2], "fmt");
   }
+
+  #[cfg(feature = "os_cmd")]
+  #[test]
+  fn test_join_quoted() {
+    assert_eq!(join_quoted(&[]), "");
+
+    let args = ["cargo", "+nightly", "fmt"].pipe(collect_boxed_ministr_slice);
+    assert_eq!(join_quoted(&args), "cargo +nightly fmt");
+
+    let args = ["printf", r#"say "hi""#].pipe(collect_boxed_ministr_slice);
+    assert_eq!(join_quoted(&args), r#"printf 'say "hi"'"#);
+  }
 }
@@ -72,6 +72,38 @@ impl DecodedText {
     self.data
   }
 
+  /// Iterates over the lines of the text.
+  ///
+  /// Splits on `\n` and trims a single trailing `\r` from each line, so
+  /// both `\n` and `\r\n` (e.g., Windows console output) are handled.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::DecodedText;
+  ///
+  /// let text = DecodedText::from("a\r\nb\n\r\nc");
+  /// assert_eq!(text.lines().collect::<Vec<_>>(), ["a", "b", "", "c"]);
+  /// ```
+  pub fn lines(&self) -> impl Iterator<Item = &str> {
+    self
+      .data
+      .split_terminator('\n')
+      .map(|line| {
+        line
+          .strip_suffix('\r')
+          .unwrap_or(line)
+      })
+  }
+
+  /// Same as [DecodedText::lines()], but skips blank (empty or
+  /// whitespace-only) lines.
+  pub fn non_empty_lines(&self) -> impl Iterator<Item = &str> {
+    self
+      .lines()
+      .filter(|line| !line.trim_ascii().is_empty())
+  }
+
   // Converts any byte-like input into `DecodedText`.
   // - If the bytes are valid UTF-8, keep it lossless.
   // - Otherwise, decode with replacement (lossy) and mark `lossy = true`.
@@ -122,4 +154,142 @@ impl DecodedText {
         .pipe(Self::new_lossless),
     }
   }
+
+  /// Decodes bytes from the encoding identified by `label` (e.g.,
+  /// `"shift_jis"`, `"gbk"`, `"windows-1252"`).
+  ///
+  /// Useful for console tools that don't emit UTF-8 (e.g., `cmd` on a
+  /// CJK-locale Windows). `lossy` is set when malformed sequences were
+  /// replaced with `U+FFFD`.
+  ///
+  /// Falls back to [DecodedText::from_vec()] (UTF-8) when:
+  ///
+  /// - the `encoding` feature is disabled;
+  /// - `label` is UTF-8 or is not a known [WHATWG encoding label](https://encoding.spec.whatwg.org/#names-and-labels).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::DecodedText;
+  ///
+  /// let text = DecodedText::from_bytes_with_encoding(b"hello".to_vec(), "utf-8");
+  /// assert_eq!(text.data(), "hello");
+  /// ```
+  pub fn from_bytes_with_encoding<V: Into<Vec<u8>>>(bytes: V, label: &str) -> Self {
+    let bytes = bytes.into();
+
+    #[cfg(feature = "encoding")]
+    match encoding_rs::Encoding::for_label(label.as_bytes()) {
+      Some(enc) if enc != encoding_rs::UTF_8 => {
+        let (text, had_errors) = enc.decode_without_bom_handling(&bytes);
+        return Self {
+          lossy: had_errors,
+          data: text.into(),
+        };
+      }
+      _ => {}
+    }
+    #[cfg(not(feature = "encoding"))]
+    let _ = label;
+
+    Self::from_vec(bytes)
+  }
+
+  /// Decodes both streams of an existing [Output](std::process::Output).
+  ///
+  /// Useful when the child was spawned via `std::process::Command` directly.
+  ///
+  /// Returns `(stdout, stderr, status)`.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use std::process::Command;
+  ///
+  /// use testutils::os_cmd::DecodedText;
+  ///
+  /// let output = Command::new("printf")
+  ///   .arg("hi")
+  ///   .output()?;
+  /// let (stdout, stderr, status) = DecodedText::from_output(output);
+  ///
+  /// assert_eq!(stdout.data(), "hi");
+  /// assert!(stderr.is_empty());
+  /// assert!(status.success());
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn from_output(
+    output: std::process::Output,
+  ) -> (Self, Self, std::process::ExitStatus) {
+    let std::process::Output {
+      status,
+      stdout,
+      stderr,
+    } = output;
+
+    (Self::from_vec(stdout), Self::from_vec(stderr), status)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lines_with_mixed_line_endings() {
+    let text = DecodedText::from("first\r\nsecond\n\r\n  \nthird\r\n");
+
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines, ["first", "second", "", "  ", "third"]);
+    assert!(
+      lines
+        .iter()
+        .all(|x| !x.contains('\r'))
+    );
+
+    let lines = text
+      .non_empty_lines()
+      .collect::<Vec<_>>();
+    assert_eq!(lines, ["first", "second", "third"]);
+  }
+
+  #[test]
+  fn from_output_with_invalid_stderr() {
+    use std::process::{ExitStatus, Output};
+
+    let output = Output {
+      status: ExitStatus::default(),
+      stdout: b"ok\n".to_vec(),
+      stderr: vec![b'e', 0xff, b'r'],
+    };
+
+    let (stdout, stderr, status) = DecodedText::from_output(output);
+    assert!(!stdout.lossy);
+    assert_eq!(stdout.data(), "ok\n");
+    assert!(stderr.lossy);
+    assert_eq!(stderr.data(), "e\u{FFFD}r");
+    assert!(status.success());
+  }
+
+  #[cfg(feature = "encoding")]
+  #[test]
+  fn decode_shift_jis() {
+    // "日本語" in Shift_JIS
+    let bytes = [0x93, 0xfa, 0x96, 0x7b, 0x8c, 0xea];
+
+    let text = DecodedText::from_bytes_with_encoding(bytes, "shift_jis");
+    assert!(!text.lossy);
+    assert_eq!(text.data(), "日本語");
+
+    // Same bytes through the UTF-8 path are lossy.
+    let text = DecodedText::from_bytes_with_encoding(bytes, "utf-8");
+    assert!(text.lossy);
+
+    // A truncated double-byte sequence is replaced.
+    let text = DecodedText::from_bytes_with_encoding([0x93, 0xfa, 0x96], "sjis");
+    assert!(text.lossy);
+    assert_eq!(text.data(), "日\u{FFFD}");
+  }
 }
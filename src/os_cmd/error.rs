@@ -0,0 +1,91 @@
+use core::fmt;
+use std::{error::Error, ffi::OsString, io, process::ExitStatus};
+
+/// Structured errors for running OS commands.
+///
+/// The run methods (e.g., [Runner::run_status()](super::Runner::run_status))
+/// still return `io::Result`, but wrap this type, so callers can recover it via
+/// `downcast`:
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use testutils::{
+///   os_cmd::{OsCmdError, Runner, RunnerInspection},
+///   tap::Pipe,
+/// };
+///
+/// let err = ["sh", "-c", "exit 2"]
+///   .pipe(Runner::from)
+///   .with_inspect_mode(RunnerInspection::None)
+///   .run_command()
+///   .expect_err("non-zero exit");
+///
+/// let Ok(OsCmdError::NonZeroExit { status, .. }) = err
+///   .into_inner()
+///   .expect("custom error")
+///   .downcast::<OsCmdError>()
+///   .map(|x| *x)
+/// else {
+///   panic!("unexpected error variant")
+/// };
+/// assert_eq!(status.code(), Some(2));
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum OsCmdError {
+  /// The argv is empty, i.e., there is no program to run.
+  EmptyCommand,
+
+  /// The program could not be spawned (e.g., not found).
+  SpawnFailed {
+    program: OsString,
+    source: io::Error,
+  },
+
+  /// The program ran, but exited with a non-zero status.
+  NonZeroExit {
+    program: OsString,
+    status: ExitStatus,
+  },
+}
+
+impl fmt::Display for OsCmdError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    use OsCmdError::*;
+    match self {
+      EmptyCommand => f.write_str("empty command argv"),
+      SpawnFailed { program, source } => {
+        write!(f, "Failed to spawn command {program:?}: {source}")
+      }
+      NonZeroExit { program, status } => {
+        write!(f, "Failed to run command {program:?}: {status}")
+      }
+    }
+  }
+}
+
+impl Error for OsCmdError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::SpawnFailed { source, .. } => Some(source),
+      _ => None,
+    }
+  }
+}
+
+impl From<OsCmdError> for io::Error {
+  /// The `ErrorKind` is derived from the variant:
+  ///
+  /// - EmptyCommand => InvalidInput
+  /// - SpawnFailed => the kind of its source (e.g., NotFound)
+  /// - NonZeroExit => Other
+  fn from(value: OsCmdError) -> Self {
+    use OsCmdError::*;
+    let kind = match &value {
+      EmptyCommand => io::ErrorKind::InvalidInput,
+      SpawnFailed { source, .. } => source.kind(),
+      NonZeroExit { .. } => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, value)
+  }
+}
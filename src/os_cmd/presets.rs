@@ -4,9 +4,19 @@ pub type CowStrVec<'a, const N: usize> = TinyVec<alloc::borrow::Cow<'a, str>, N>
 pub type MiniStrVec<const N: usize> = TinyVec<crate::os_cmd::MiniStr, N>;
 
 pub mod cargo_build;
+mod cargo_clippy;
 mod cargo_doc;
 mod cargo_fmt;
+mod cargo_nextest;
+mod cargo_run;
+mod cargo_test;
+mod cfg_builder;
 
 pub use cargo_build::CargoCmd;
+pub use cargo_clippy::CargoClippy;
 pub use cargo_doc::CargoDoc;
 pub use cargo_fmt::CargoFmt;
+pub use cargo_nextest::CargoNextest;
+pub use cargo_run::CargoRun;
+pub use cargo_test::CargoTest;
+pub use cfg_builder::CfgBuilder;
@@ -1,11 +1,15 @@
+use alloc::{boxed::Box, vec::Vec};
 use core::fmt::Display;
-use std::env;
 
 use compact_str::ToCompactString;
 use getset::{Getters, WithSetters};
-use tap::{Pipe, Tap};
+use tap::Pipe;
+#[cfg(feature = "os_cmd")]
+use tap::Tap;
 
-use crate::os_cmd::{CommandRepr, MiniStr, RunnableCommand, fmt_compact};
+use crate::os_cmd::{MiniStr, fmt_compact};
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{RunnableCommand, Runner};
 
 mod sub_cmd;
 pub use sub_cmd::SubCmd;
@@ -22,28 +26,52 @@ mod build_std;
 pub use build_std::BuildStd;
 
 mod build_std_features;
-pub use build_std_features::BuildStdFeatures;
-impl RunnableCommand<'_> for CargoCmd {}
+pub use build_std_features::{BuildStdError, BuildStdFeatures};
+
+mod network_flags;
+pub use network_flags::NetworkFlags;
+
+mod verbosity;
+pub use verbosity::Verbosity;
+
+mod message_format;
+pub use message_format::MessageFormat;
+
+mod feature_set;
+pub use feature_set::FeatureSet;
+
+mod parse_error;
+pub use parse_error::ParseError;
+
+#[cfg(feature = "serde")]
+mod serde_str;
+
+#[cfg(feature = "os_cmd")]
+impl<'a> RunnableCommand<'a> for CargoCmd {
+  /// Applies `RUSTFLAGS` to the child process only, so `run()` and
+  /// `into_spawner()` pick it up as well.
+  ///
+  /// See also: [CargoCmd::into_runner()]
+  fn into_runner(self) -> Runner<'a> {
+    CargoCmd::into_runner(self)
+  }
+}
 
 #[derive(Debug, Clone, WithSetters, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", get = "pub with_prefix")]
 /// Configurable cargo build command.
 ///
 /// ```
-/// use testutils::{
-///   get_pkg_name,
-///   os_cmd::{
-///     Runner,
-///     presets::{
-///       CargoCmd,
-///       cargo_build::{BuildStd, BuildStdFeatures},
-///     },
-///   },
+/// use testutils::os_cmd::presets::{
+///   CargoCmd,
+///   cargo_build::{BuildStd, BuildStdFeatures},
 /// };
 ///
 /// let vec = CargoCmd::default()
 ///   .with_nightly(true)
-///   .with_pkg(get_pkg_name!().into())
+///   .with_pkg("testutils".into())
 ///   .with_build_std(
 ///     BuildStd::default()
 ///       .with_alloc(true)
@@ -68,24 +96,55 @@ impl RunnableCommand<'_> for CargoCmd {}
 ///   ]
 /// );
 ///
-/// let _runner: Runner = vec.into();
+/// # #[cfg(feature = "os_cmd")]
+/// let _runner: testutils::os_cmd::Runner = vec.into();
 /// // runner.run();
 /// ```
 ///
+/// `into_vec()` only builds the argv. `rust_flags` is passed to the child
+/// process as the `RUSTFLAGS` environment variable when the command is
+/// converted via [CargoCmd::into_runner()] or run via
+/// [RunnableCommand::run()].
+///
 /// See also: [The cargo book](https://doc.rust-lang.org/cargo/reference/unstable.html)
 pub struct CargoCmd {
   rust_flags: flags::RustFlags,
+  /// When a target is set, passes `rust_flags` via
+  /// `CARGO_TARGET_<TRIPLE>_RUSTFLAGS` instead of the global `RUSTFLAGS`, so
+  /// build scripts and proc-macros (host) are not affected.
+  target_scoped_rustflags: bool,
+  /// Appends `rust_flags` to the value of the same env var in the current
+  /// process (space-joined), instead of replacing it.
+  ///
+  /// Requires the `std` feature; otherwise, this has no effect.
+  inherit_rustflags: bool,
   // use_os_cmd_to_set_env: bool,
   nightly: bool,
   cargo: MiniStr,
   sub_command: SubCmd,
+  verbosity: Verbosity,
+  network_flags: NetworkFlags,
   profile: CargoProfile,
   pkg: MiniStr,
   target: CargoTarget,
   all_packages: bool,
+  /// `--exclude={pkg}`, repeated for each pkg.
+  ///
+  /// Only takes effect together with `all_packages` (`--workspace`);
+  /// otherwise it is ignored and a warning is logged.
+  exclude: Box<[MiniStr]>,
+  /// `--lib`
+  lib: bool,
+  /// `--bin={name}`, repeated for each name.
+  bins: Box<[MiniStr]>,
+  /// `--example={name}`, repeated for each name.
+  examples: Box<[MiniStr]>,
   all_features: bool,
   no_default_features: bool,
   features: Box<[MiniStr]>,
+  /// `--jobs={n}`
+  jobs: Option<usize>,
+  message_format: MessageFormat,
   build_std: BuildStd,
   build_std_features: BuildStdFeatures,
   extra_args: Box<[MiniStr]>,
@@ -98,6 +157,7 @@ impl Default for CargoCmd {
   /// CargoCmd {
   ///     rust_flags: RustFlags {
   ///         crt_static: None,
+  ///         target_features: [],
   ///         prefer_dynamic: None,
   ///         linker: "",
   ///         linker_flavor: Ignore,
@@ -105,19 +165,39 @@ impl Default for CargoCmd {
   ///         relocation_model: Ignore,
   ///         code_model: Ignore,
   ///         codegen_units: None,
+  ///         opt_level: None,
+  ///         debug_assertions: None,
+  ///         overflow_checks: None,
+  ///         lto: Ignore,
+  ///         strip: Ignore,
+  ///         split_debuginfo: Ignore,
   ///         native_target_cpu: None,
   ///         other_flags: [],
   ///     },
+  ///     target_scoped_rustflags: false,
+  ///     inherit_rustflags: false,
   ///     nightly: false,
   ///     cargo: "cargo",
   ///     sub_command: Build,
+  ///     verbosity: Normal,
+  ///     network_flags: NetworkFlags {
+  ///         locked: false,
+  ///         offline: false,
+  ///         frozen: false,
+  ///     },
   ///     profile: Release,
   ///     pkg: "",
   ///     target: default,
   ///     all_packages: false,
+  ///     exclude: [],
+  ///     lib: false,
+  ///     bins: [],
+  ///     examples: [],
   ///     all_features: false,
   ///     no_default_features: false,
   ///     features: [],
+  ///     jobs: None,
+  ///     message_format: Human,
   ///     build_std: BuildStd {
   ///         build_default: false,
   ///         std: false,
@@ -148,16 +228,26 @@ impl Default for CargoCmd {
   fn default() -> Self {
     Self {
       rust_flags: Default::default(),
+      target_scoped_rustflags: false,
+      inherit_rustflags: false,
       nightly: false,
       cargo: "cargo".into(),
       sub_command: Default::default(),
+      verbosity: Default::default(),
+      network_flags: Default::default(),
       profile: Default::default(),
       pkg: "".into(),
       target: Default::default(),
       all_packages: false,
+      exclude: Default::default(),
+      lib: false,
+      bins: Default::default(),
+      examples: Default::default(),
       all_features: false,
       no_default_features: false,
       features: Default::default(),
+      jobs: None,
+      message_format: Default::default(),
       build_std: Default::default(),
       build_std_features: Default::default(),
       extra_args: Default::default(),
@@ -187,33 +277,294 @@ pub trait ArgConverter {
 }
 
 impl CargoCmd {
+  /// Returns `("RUSTFLAGS", flags)`, or `None` if no rust flags are set.
+  ///
+  /// With `target_scoped_rustflags` and a non-empty target, the key is
+  /// `CARGO_TARGET_<TRIPLE>_RUSTFLAGS` (see [CargoCmd::rustflags_env_key()]).
+  ///
+  /// With `inherit_rustflags`, the existing value of that env var (if any) is
+  /// prepended. Either way, the environment of the current process is not
+  /// modified.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::{
+  ///   CargoCmd,
+  ///   cargo_build::{RustcTarget, flags::RustFlags},
+  /// };
+  ///
+  /// let (key, value) = CargoCmd::default()
+  ///   .with_target(RustcTarget::x86_64_unknown_linux_musl.into())
+  ///   .with_target_scoped_rustflags(true)
+  ///   .with_rust_flags(RustFlags::default().with_crt_static(true.into()))
+  ///   .rustflags_env()
+  ///   .expect("rust flags");
+  ///
+  /// assert_eq!(key, "CARGO_TARGET_X86_64_UNKNOWN_LINUX_MUSL_RUSTFLAGS");
+  /// assert_eq!(value, "-C target-feature=+crt-static");
+  /// ```
+  pub fn rustflags_env(&self) -> Option<(MiniStr, MiniStr)> {
+    self
+      .rust_flags
+      .clone()
+      .into_env()
+      .pipe(|(_, x)| match x.is_empty() {
+        true => None,
+        _ => self
+          .rustflags_env_key()
+          .pipe(|key| {
+            let value = self.inherited_rustflags(&key, x);
+            (key, value)
+          })
+          .pipe(Some),
+      })
+  }
+
+  /// Prepends the current value of `key` if `inherit_rustflags` is enabled.
+  #[cfg(feature = "std")]
+  fn inherited_rustflags(&self, key: &str, flags: MiniStr) -> MiniStr {
+    if !self.inherit_rustflags {
+      return flags;
+    }
+    match std::env::var(key) {
+      Ok(old) if !old.trim().is_empty() => fmt_compact!("{} {flags}", old.trim()),
+      _ => flags,
+    }
+  }
+
+  #[cfg(not(feature = "std"))]
+  fn inherited_rustflags(&self, _key: &str, flags: MiniStr) -> MiniStr {
+    flags
+  }
+
+  /// The env key used by [CargoCmd::rustflags_env()]:
+  ///
+  /// - `target_scoped_rustflags` && target is set =>
+  ///   `CARGO_TARGET_<TRIPLE>_RUSTFLAGS`, where `<TRIPLE>` is uppercased and
+  ///   `-`/`.` become `_` (same as cargo).
+  /// - otherwise => `RUSTFLAGS`
+  pub fn rustflags_env_key(&self) -> MiniStr {
+    match self.target.as_str() {
+      t if !self.target_scoped_rustflags || t.is_empty() => "RUSTFLAGS".into(),
+      t => t
+        .chars()
+        .map(|c| match c {
+          '-' | '.' => '_',
+          c => c.to_ascii_uppercase(),
+        })
+        .collect::<MiniStr>()
+        .pipe(|triple| fmt_compact!("CARGO_TARGET_{triple}_RUSTFLAGS")),
+    }
+  }
+
+  /// Clones the command once per target, with `target` set accordingly.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(feature = "os_cmd")] {
+  /// use testutils::os_cmd::{
+  ///   Runner,
+  ///   presets::{CargoCmd, cargo_build::RustcTarget},
+  /// };
+  ///
+  /// let runners = CargoCmd::default()
+  ///   .for_targets([
+  ///     RustcTarget::x86_64_unknown_linux_musl,
+  ///     RustcTarget::wasm32_unknown_unknown,
+  ///   ])
+  ///   .into_iter()
+  ///   .map(CargoCmd::into_runner)
+  ///   .collect::<Vec<Runner>>();
+  /// assert_eq!(runners.len(), 2);
+  /// # }
+  /// ```
+  pub fn for_targets<I>(self, targets: I) -> Vec<CargoCmd>
+  where
+    I: IntoIterator<Item = RustcTarget>,
+  {
+    targets
+      .into_iter()
+      .map(|target| {
+        self
+          .clone()
+          .with_target(target.into())
+      })
+      .collect()
+  }
+
+  /// Appends a single arg to `extra_args`.
+  ///
+  /// Unlike `with_extra_args`, this keeps the previously configured args.
+  pub fn push_extra_arg<S: Into<MiniStr>>(self, arg: S) -> Self {
+    self.extend_extra_args([arg])
+  }
+
+  /// Appends args to `extra_args`.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::CargoCmd;
+  ///
+  /// let verbose = true;
+  /// let cmd = CargoCmd::default().extend_extra_args(["--locked"]);
+  /// let cmd = match verbose {
+  ///   true => cmd.push_extra_arg("-v"),
+  ///   _ => cmd,
+  /// };
+  ///
+  /// assert_eq!(
+  ///   cmd.into_vec(),
+  ///   ["cargo", "build", "--profile=release", "--locked", "-v"]
+  /// );
+  /// ```
+  pub fn extend_extra_args<I, S>(mut self, iter: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<MiniStr>,
+  {
+    self.extra_args = core::mem::take(&mut self.extra_args)
+      .into_iter()
+      .chain(iter.into_iter().map(Into::into))
+      .collect();
+    self
+  }
+
+  /// Converts CargoCmd into a Runner, with `RUSTFLAGS` (if any) set as a
+  /// per-command environment variable.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::{CargoCmd, cargo_build::flags::RustFlags};
+  ///
+  /// let runner = CargoCmd::default()
+  ///   .with_rust_flags(RustFlags::default().with_crt_static(true.into()))
+  ///   .into_runner();
+  ///
+  /// assert_eq!(
+  ///   runner.get_envs().as_deref(),
+  ///   Some(&[("RUSTFLAGS".into(), "-C target-feature=+crt-static".into())][..])
+  /// );
+  /// ```
+  #[cfg(feature = "os_cmd")]
+  pub fn into_runner<'a>(self) -> Runner<'a> {
+    let env = self
+      .rustflags_env()
+      .tap(|x| log::debug!("RUSTFLAGS: {x:?}"));
+
+    self
+      .into_vec()
+      .pipe(Runner::from)
+      .extend_envs(env)
+  }
+
   /// Collects all CargoCmd options into a vec
-  #[allow(clippy::unnecessary_lazy_evaluations)]
+  ///
+  /// Note: `rust_flags` is not part of the argv, see
+  /// [CargoCmd::rustflags_env()].
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::CargoCmd;
+  ///
+  /// let vec = CargoCmd::default()
+  ///   .with_jobs(Some(8))
+  ///   .into_vec();
+  /// assert_eq!(vec, ["cargo", "build", "--profile=release", "--jobs=8"]);
+  /// ```
+  ///
+  /// ### Workspace
+  ///
+  /// ```
+  /// use testutils::os_cmd::{collect_boxed_ministr_slice, presets::CargoCmd};
+  ///
+  /// let vec = CargoCmd::default()
+  ///   .with_all_packages(true)
+  ///   .with_exclude(collect_boxed_ministr_slice(["xtask"]))
+  ///   .into_vec();
+  /// assert_eq!(
+  ///   vec,
+  ///   [
+  ///     "cargo",
+  ///     "build",
+  ///     "--profile=release",
+  ///     "--workspace",
+  ///     "--exclude=xtask"
+  ///   ]
+  /// );
+  /// ```
+  ///
+  /// ### Target selection
+  ///
+  /// ```
+  /// use testutils::os_cmd::{collect_boxed_ministr_slice, presets::CargoCmd};
+  ///
+  /// let vec = CargoCmd::default()
+  ///   .with_lib(true)
+  ///   .with_bins(collect_boxed_ministr_slice(["app"]))
+  ///   .into_vec();
+  /// assert_eq!(
+  ///   vec,
+  ///   ["cargo", "build", "--profile=release", "--lib", "--bin=app"]
+  /// );
+  /// ```
   pub fn into_vec(self) -> Vec<MiniStr> {
+    self.into_args_iter().collect()
+  }
+
+  /// Same sequence as [CargoCmd::into_vec()], but produced lazily, so it can be
+  /// composed with other iterator chains.
+  ///
+  /// Like `into_vec`, it has no side effects: `rust_flags` is not part of the
+  /// argv, see [CargoCmd::rustflags_env()].
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::CargoCmd;
+  ///
+  /// let argv = CargoCmd::default()
+  ///   .into_args_iter()
+  ///   .chain(["--".into(), "--nocapture".into()])
+  ///   .collect::<Vec<_>>();
+  /// assert_eq!(
+  ///   argv,
+  ///   ["cargo", "build", "--profile=release", "--", "--nocapture"]
+  /// );
+  /// ```
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_args_iter(self) -> impl Iterator<Item = MiniStr> {
     let CargoCmd {
-      rust_flags,
+      rust_flags: _,
+      target_scoped_rustflags: _,
+      inherit_rustflags: _,
       cargo,
       sub_command,
+      verbosity,
+      network_flags,
       nightly,
       profile,
       pkg,
       target,
       all_packages,
+      exclude,
+      lib,
+      bins,
+      examples,
       all_features,
       no_default_features,
       features,
+      jobs,
+      message_format,
       build_std,
       build_std_features,
       extra_args,
     } = self;
 
-    let rust_flags_value = rust_flags
-      .into_vec()
-      .join(" ")
-      .tap(|x| log::debug!("setenv: RUSTFLAGS={x}"));
-
-    unsafe { env::set_var("RUSTFLAGS", rust_flags_value) }
-
     match cargo {
       c if c.is_empty() => "cargo".into(),
       c => c,
@@ -226,12 +577,40 @@ impl CargoCmd {
         .to_compact_string()
         .pipe(Some),
     )
+    // -q, -v, -vv
+    .chain(verbosity.to_args())
+    // --locked --offline --frozen
+    .chain(network_flags.to_args())
     // --profile {profile}
     .chain(try_into_long_arg("profile", profile))
     // --package {pkg}
     .chain(try_into_long_arg("package", pkg))
     // --workspace
     .chain(all_packages.then(|| "--workspace".into()))
+    // --exclude={pkg}...
+    .chain(
+      match all_packages {
+        false if !exclude.is_empty() => {
+          log::warn!("`exclude` requires `all_packages` (--workspace), ignored.");
+          Default::default()
+        }
+        _ => exclude,
+      }
+      .into_iter()
+      .filter_map(|x| try_into_long_arg("exclude", x)),
+    )
+    // --lib --bin={bin}... --example={example}...
+    .chain(lib.then(|| "--lib".into()))
+    .chain(
+      bins
+        .into_iter()
+        .filter_map(|x| try_into_long_arg("bin", x)),
+    )
+    .chain(
+      examples
+        .into_iter()
+        .filter_map(|x| try_into_long_arg("example", x)),
+    )
     // --target {target.as_ref()}
     .chain(try_into_long_arg("target", target))
     .chain(all_features.then(|| "--all-features".into()))
@@ -241,37 +620,291 @@ impl CargoCmd {
       x if x.is_empty() => None,
       feats => Some(fmt_compact!("--features={}", feats.join(","))),
     })
+    // --jobs={n}
+    .chain(jobs.map(|n| fmt_compact!("--jobs={n}")))
+    // --message-format={format}
+    .chain(message_format.to_args())
     // --build-std {build_std.to_args()}
     .chain(build_std.to_args())
     .chain(build_std_features.to_args())
     .chain(extra_args)
-    .collect()
   }
 }
 
-impl From<CargoCmd> for CommandRepr<'_> {
+/// Same as [CargoCmd::into_runner()], so `Runner::from(cmd)` and
+/// `CommandSpawner::from(cmd)` keep `rust_flags` as a per-command env.
+///
+/// There is no `From<CargoCmd> for CommandRepr`: a bare argv would drop
+/// `rust_flags`.
+#[cfg(feature = "os_cmd")]
+impl From<CargoCmd> for Runner<'_> {
   fn from(value: CargoCmd) -> Self {
-    value
-      .into_vec()
-      .into_boxed_slice()
-      .pipe(CommandRepr::OwnedSlice)
+    value.into_runner()
   }
 }
 
-// impl From<CargoCmd> for Runner<'_> {
-//   fn from(value: CargoCmd) -> Self {
-//     Self::default() //
-//       .with_command(value.into())
-//   }
-// }
-
 #[cfg(test)]
 mod tests {
   use tap::Pipe;
 
-  // use super::*;
+  use super::*;
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn into_vec_keeps_process_env() {
+    let before = std::env::var_os("RUSTFLAGS");
+    let cmd = CargoCmd::default()
+      .with_rust_flags(flags::RustFlags::default().with_crt_static(true.into()));
+
+    for _ in 0..2 {
+      let vec = cmd.clone().into_vec();
+      assert_eq!(vec, ["cargo", "build", "--profile=release"]);
+    }
+    assert_eq!(std::env::var_os("RUSTFLAGS"), before);
+
+    let runner = cmd.into_runner();
+    assert_eq!(
+      runner.get_envs().as_deref(),
+      Some(&[("RUSTFLAGS".into(), "-C target-feature=+crt-static".into())][..])
+    );
+    assert_eq!(std::env::var_os("RUSTFLAGS"), before);
+  }
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn target_scoped_rustflags_env_key() {
+    let cmd = CargoCmd::default()
+      .with_target(RustcTarget::aarch64_linux_android.into())
+      .with_rust_flags(flags::RustFlags::default().with_crt_static(true.into()));
+
+    // Global by default
+    assert_eq!(cmd.rustflags_env_key(), "RUSTFLAGS");
+
+    let runner = cmd
+      .clone()
+      .with_target_scoped_rustflags(true)
+      .into_runner();
+    assert_eq!(
+      runner.get_envs().as_deref(),
+      Some(
+        &[(
+          "CARGO_TARGET_AARCH64_LINUX_ANDROID_RUSTFLAGS".into(),
+          "-C target-feature=+crt-static".into()
+        )][..]
+      )
+    );
+
+    // Without a target, it falls back to the global key.
+    let key = cmd
+      .with_target(Default::default())
+      .with_target_scoped_rustflags(true)
+      .rustflags_env_key();
+    assert_eq!(key, "RUSTFLAGS");
+  }
+
+  #[test]
+  fn args_iter_matches_into_vec() {
+    let before = std::env::var_os("RUSTFLAGS");
+    let cmd = CargoCmd::default()
+      .with_nightly(true)
+      .with_all_features(true)
+      .with_jobs(Some(2))
+      .with_rust_flags(flags::RustFlags::default().with_crt_static(true.into()))
+      .with_build_std(BuildStd::default().with_core(true));
+
+    assert_eq!(
+      cmd
+        .clone()
+        .into_args_iter()
+        .collect::<Vec<_>>(),
+      cmd.into_vec()
+    );
+    assert_eq!(std::env::var_os("RUSTFLAGS"), before);
+  }
+
+  #[test]
+  fn push_extra_args() {
+    let vec = CargoCmd::default()
+      .with_extra_args(["--locked"].pipe(crate::os_cmd::collect_boxed_ministr_slice))
+      .push_extra_arg("--offline")
+      .push_extra_arg(MiniStr::from("-v"))
+      .into_vec();
+
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "build",
+        "--profile=release",
+        "--locked",
+        "--offline",
+        "-v"
+      ]
+    );
+  }
+
+  #[test]
+  fn preset_enums_eq() {
+    use flags::{CodeModel, RelocationModel};
+
+    assert_eq!(SubCmd::from("build"), SubCmd::Build);
+    assert_eq!(SubCmd::from("doc"), SubCmd::Custom("doc".into()));
+    assert_ne!(SubCmd::from("doc"), SubCmd::Custom("fix".into()));
+    assert_eq!(CodeModel::from("large"), CodeModel::Large);
+    assert_ne!(RelocationModel::default(), RelocationModel::Pic);
+  }
+
+  #[test]
+  fn verbosity_after_sub_command() {
+    let vec = CargoCmd::default()
+      .with_sub_command(SubCmd::Check)
+      .with_verbosity(Verbosity::VeryVerbose)
+      .into_vec();
+    assert_eq!(vec, ["cargo", "check", "-vv", "--profile=release"]);
+  }
+
+  #[test]
+  fn json_message_format() {
+    let vec = CargoCmd::default()
+      .with_message_format(MessageFormat::Json)
+      .into_vec();
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "build",
+        "--profile=release",
+        "--message-format=json"
+      ]
+    );
+  }
+
+  #[test]
+  fn exclude_without_workspace_is_ignored() {
+    use crate::os_cmd::collect_boxed_ministr_slice;
+
+    let vec = CargoCmd::default()
+      .with_exclude(["xtask"].pipe(collect_boxed_ministr_slice))
+      .into_vec();
+    assert_eq!(vec, ["cargo", "build", "--profile=release"]);
+  }
+
+  #[test]
+  fn build_for_multiple_targets() {
+    use RustcTarget::*;
+
+    let targets = [
+      aarch64_unknown_linux_gnu,
+      x86_64_pc_windows_msvc,
+      riscv64gc_unknown_linux_gnu,
+    ];
+    let cmds = CargoCmd::default()
+      .with_jobs(Some(2))
+      .for_targets(targets);
+    assert_eq!(cmds.len(), 3);
+
+    for (cmd, target) in cmds.into_iter().zip(targets) {
+      let vec = cmd.into_vec();
+      assert!(vec.contains(&fmt_compact!("--target={target}")));
+      assert!(vec.contains(&"--jobs=2".into()));
+    }
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_json_round_trip() {
+    use flags::{Lto, RustFlags};
+
+    let cmd = CargoCmd::default()
+      .with_nightly(true)
+      .with_sub_command(SubCmd::Custom("zigbuild".into()))
+      .with_profile(CargoProfile::Custom("bench".into()))
+      .with_target(RustcTarget::aarch64_unknown_linux_musl.into())
+      .with_message_format(MessageFormat::JsonDiagnosticShort)
+      .with_features(
+        ["std", "serde"]
+          .map(MiniStr::from)
+          .into(),
+      )
+      .with_rust_flags(RustFlags::default().with_lto(Lto::Thin))
+      .with_build_std(BuildStd::default().with_core(true));
+
+    let json = serde_json::to_string(&cmd).expect("serialize CargoCmd");
+    assert!(json.contains(r#""profile":"bench""#), "{json}");
+    assert!(json.contains(r#""lto":"thin""#), "{json}");
+
+    let parsed: CargoCmd =
+      serde_json::from_str(&json).expect("deserialize CargoCmd");
+    assert_eq!(parsed.rustflags_env(), cmd.rustflags_env());
+    assert_eq!(parsed.into_vec(), cmd.into_vec());
+
+    // Missing fields fall back to `Default`.
+    let partial: CargoCmd =
+      serde_json::from_str(r#"{"sub_command":"test"}"#).expect("partial CargoCmd");
+    assert_eq!(partial.into_vec(), ["cargo", "test", "--profile=release"]);
+  }
+
+  #[test]
+  fn repeated_target_selectors() {
+    use crate::os_cmd::collect_boxed_ministr_slice;
+
+    let vec = CargoCmd::default()
+      .with_bins(["a", "", "b"].pipe(collect_boxed_ministr_slice))
+      .with_examples(["demo"].pipe(collect_boxed_ministr_slice))
+      .into_vec();
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "build",
+        "--profile=release",
+        "--bin=a",
+        "--bin=b",
+        "--example=demo"
+      ]
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn into_runner_without_rust_flags() {
+    let runner = CargoCmd::default().into_runner();
+    assert!(runner.get_envs().is_none());
+  }
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn runner_from_keeps_rust_flags() {
+    use crate::os_cmd::Runner;
+
+    let flags = flags::RustFlags::default().with_crt_static(true.into());
+
+    let runner = CargoCmd::default()
+      .with_rust_flags(flags.clone())
+      .pipe(Runner::from);
+    assert_eq!(
+      runner.get_envs().as_deref(),
+      Some(&[("RUSTFLAGS".into(), "-C target-feature=+crt-static".into())][..])
+    );
+
+    let runner: Runner = CargoCmd::default()
+      .with_rust_flags(flags)
+      .with_target("x86_64-unknown-linux-musl".into())
+      .with_target_scoped_rustflags(true)
+      .into();
+    assert_eq!(
+      runner.get_envs().as_deref(),
+      Some(
+        &[(
+          "CARGO_TARGET_X86_64_UNKNOWN_LINUX_MUSL_RUSTFLAGS".into(),
+          "-C target-feature=+crt-static".into()
+        )][..]
+      )
+    );
+  }
 
   #[test]
+  #[cfg(feature = "os_cmd")]
   #[ignore]
   fn test_cargo_build_command() {
     use crate::{
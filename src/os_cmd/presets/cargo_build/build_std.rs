@@ -9,6 +9,8 @@ use crate::{
 };
 
 #[derive(Debug, Clone, WithSetters, CopyGetters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", get_copy = "pub with_prefix")]
 /// Represents the build options for the standard library components.
 ///
@@ -33,6 +35,17 @@ use crate::{
 ///
 /// You can control the components to be compiled using the `with_` methods.
 ///
+/// ## Canonical order
+///
+/// The components are always emitted in this fixed order (a stable
+/// guarantee), regardless of the order in which they were toggled:
+///
+/// `std, core, alloc, panic_abort, panic_unwind, test, proc_macro`
+///
+/// Since each component is a single `bool` field, it appears at most once.
+/// So the same set of components always produces the same string, e.g.,
+/// `build-std=core,alloc`.
+///
 /// ## Example
 ///
 /// ```
@@ -122,9 +135,13 @@ impl ArgConverter for BuildStd {
   /// This method generates a list of arguments based on the enabled fields
   /// in the `BuildStd` struct. If no fields are enabled, it returns `None`.
   /// If `build_default` is enabled, it returns the default build arguments.
+  ///
+  /// The components follow the canonical order, see [BuildStd].
   fn to_args(&self) -> Self::ArgsIter {
     // **Note:** The `build_default` is special. Do not include `build_default` in
     // the `generate_struct_arr!` macro.
+    //
+    // The order below is the documented canonical order; keep it stable.
     let components = generate_struct_arr! [ self =>
       std,
       core,
@@ -168,6 +185,24 @@ mod tests {
     BuildStd::default().pipe(|x| dbg!(x));
   }
 
+  #[test]
+  fn canonical_order_regardless_of_toggling() {
+    let toggled = BuildStd::default()
+      .with_core(true)
+      .with_alloc(true)
+      .with_core(true);
+    let reversed = BuildStd::default()
+      .with_alloc(true)
+      .with_core(true);
+
+    for b in [toggled, reversed] {
+      assert_eq!(
+        b.to_args().collect::<Vec<_>>(),
+        ["-Z", "build-std=core,alloc"]
+      );
+    }
+  }
+
   #[ignore]
   #[test]
   fn test_build_std_to_args() {
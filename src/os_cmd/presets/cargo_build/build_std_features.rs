@@ -34,6 +34,8 @@ use crate::{
 ///   .map(|x| assert_eq!(x, "build-std-features=panic_unwind,optimize_for_size"));
 /// ```
 #[derive(Debug, Clone, WithSetters, CopyGetters, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", get_copy = "pub with_prefix")]
 pub struct BuildStdFeatures {
   /// Immediately abort on panic rather than unwinding
@@ -83,6 +85,10 @@ impl ArgConverter for BuildStdFeatures {
   ///
   /// The generated arguments are suitable for passing to cargo's unstable `-Z`
   /// flag.
+  ///
+  /// Conflicting features are not checked here: e.g., `panic_immediate_abort`
+  /// and `panic_unwind` are just both emitted. Use
+  /// [BuildStdFeatures::try_to_args()] to reject them.
   fn to_args(&self) -> Self::ArgsIter {
     let components = generate_struct_arr! [ self =>
         panic_immediate_abort,
@@ -119,6 +125,51 @@ impl ArgConverter for BuildStdFeatures {
   }
 }
 
+/// Errors of [BuildStdFeatures::try_to_args()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStdError {
+  /// Both `panic_immediate_abort` and `panic_unwind` are enabled.
+  ConflictingPanicStrategy,
+}
+
+impl core::fmt::Display for BuildStdError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::ConflictingPanicStrategy => f.write_str(
+        "build-std-features: `panic_immediate_abort` and `panic_unwind` are \
+         mutually exclusive",
+      ),
+    }
+  }
+}
+
+impl core::error::Error for BuildStdError {}
+
+impl BuildStdFeatures {
+  /// Like [ArgConverter::to_args()], but rejects contradictory features.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::{BuildStdError, BuildStdFeatures};
+  ///
+  /// let err = BuildStdFeatures::default()
+  ///   .with_panic_immediate_abort(true)
+  ///   .with_panic_unwind(true)
+  ///   .try_to_args()
+  ///   .unwrap_err();
+  /// assert_eq!(err, BuildStdError::ConflictingPanicStrategy);
+  /// ```
+  pub fn try_to_args(
+    &self,
+  ) -> Result<<Self as ArgConverter>::ArgsIter, BuildStdError> {
+    match (self.panic_immediate_abort, self.panic_unwind) {
+      (true, true) => Err(BuildStdError::ConflictingPanicStrategy),
+      _ => Ok(self.to_args()),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -132,6 +183,41 @@ mod tests {
     assert!(!feats.get_panic_immediate_abort());
   }
 
+  #[test]
+  fn reject_conflicting_panic_strategy() {
+    let feats = BuildStdFeatures::default()
+      .with_panic_immediate_abort(true)
+      .with_panic_unwind(true);
+
+    assert!(matches!(
+      feats.try_to_args(),
+      Err(BuildStdError::ConflictingPanicStrategy)
+    ));
+    // The infallible version still emits both.
+    assert_eq!(
+      feats
+        .to_args()
+        .last()
+        .as_deref(),
+      Some("build-std-features=panic_immediate_abort,panic_unwind")
+    );
+
+    let args = feats
+      .with_panic_unwind(false)
+      .try_to_args()
+      .map(|x| x.collect::<alloc::vec::Vec<_>>());
+    assert_eq!(
+      args,
+      Ok(
+        [
+          "-Z".into(),
+          "build-std-features=panic_immediate_abort".into()
+        ]
+        .into()
+      )
+    );
+  }
+
   #[ignore]
   #[test]
   fn test_std_feats() {
@@ -144,8 +230,8 @@ mod tests {
     assert!(!feats.get_panic_immediate_abort());
     let args = feats.to_args();
 
-    args
-      .last()
-      .map(|x| assert_eq!(x, "build-std-features=panic_unwind,optimize_for_size"));
+    if let Some(x) = args.last() {
+      assert_eq!(x, "build-std-features=panic_unwind,optimize_for_size")
+    }
   }
 }
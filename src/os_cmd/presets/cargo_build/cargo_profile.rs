@@ -1,4 +1,4 @@
-use crate::os_cmd::MiniStr;
+use crate::os_cmd::{MiniStr, presets::cargo_build::ParseError};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// cargo profile name: e.g., build, dev
@@ -28,6 +28,19 @@ impl From<&str> for CargoProfile {
   }
 }
 
+impl core::str::FromStr for CargoProfile {
+  type Err = ParseError;
+
+  /// Like `From<&str>`, but only accepts the built-in profiles ("debug",
+  /// "dev", "release"). Use `From<&str>` for custom profiles.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match Self::from(s) {
+      Self::Custom(_) => Err(ParseError::new("cargo profile", s)),
+      x => Ok(x),
+    }
+  }
+}
+
 impl CargoProfile {
   /// Converts CargoProfile as `&str`
   pub fn as_str(&self) -> &str {
@@ -37,6 +50,25 @@ impl CargoProfile {
       Self::Custom(s) => s.as_ref(),
     }
   }
+
+  /// Reads the `PROFILE` env var (set by cargo for build scripts), e.g.,
+  /// `debug` => `Debug`.
+  ///
+  /// Falls back to `Release` (the default) when it is unset or not valid
+  /// UTF-8.
+  #[cfg(feature = "std")]
+  pub fn from_env() -> Self {
+    let profile = std::env::var("PROFILE").ok();
+    Self::from_env_value(profile.as_deref())
+  }
+
+  /// Same as [CargoProfile::from_env()], with the value of `PROFILE` passed
+  /// in (`None` if unset).
+  pub fn from_env_value(profile: Option<&str>) -> Self {
+    profile
+      .map(Self::from)
+      .unwrap_or_default()
+  }
 }
 impl AsRef<str> for CargoProfile {
   fn as_ref(&self) -> &str {
@@ -56,4 +88,32 @@ mod tests {
     assert_eq!(profile, "dev".into());
     assert_eq!(profile.as_str(), "dev");
   }
+
+  #[test]
+  fn parse_profile_and_sub_cmd() {
+    use crate::os_cmd::presets::cargo_build::SubCmd;
+
+    assert_eq!("dev".parse(), Ok(CargoProfile::Debug));
+    assert!(
+      "my-profile"
+        .parse::<CargoProfile>()
+        .is_err()
+    );
+
+    assert_eq!("test".parse(), Ok(SubCmd::Test));
+    assert!(
+      "tset"
+        .parse::<SubCmd>()
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn profile_from_env() {
+    assert_eq!(
+      CargoProfile::from_env_value(Some("dev")),
+      CargoProfile::Debug
+    );
+    assert_eq!(CargoProfile::from_env_value(None), CargoProfile::Release);
+  }
 }
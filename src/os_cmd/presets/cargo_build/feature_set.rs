@@ -0,0 +1,150 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use tap::Pipe;
+
+use crate::os_cmd::{MiniStr, presets::CargoCmd};
+
+#[derive(Debug, Clone, Default)]
+/// A cargo feature matrix: features that are always enabled, plus groups of
+/// mutually exclusive features ("exactly one of").
+///
+/// [FeatureSet::combinations()] expands every valid feature list, i.e., the
+/// required features + one feature of each group.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::{CargoCmd, cargo_build::FeatureSet};
+///
+/// let set = FeatureSet::default()
+///   .required(&["std"])
+///   .one_of(&["tls-rustls", "tls-native"]);
+///
+/// let argv = set
+///   .cargo_cmds(&CargoCmd::default())
+///   .map(CargoCmd::into_vec)
+///   .map(|v| {
+///     v.last()
+///       .cloned()
+///       .unwrap_or_default()
+///   })
+///   .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///   argv,
+///   ["--features=std,tls-rustls", "--features=std,tls-native"]
+/// );
+/// ```
+pub struct FeatureSet {
+  required: Vec<MiniStr>,
+  groups: Vec<Box<[MiniStr]>>,
+}
+
+impl FeatureSet {
+  /// Appends features that are part of every combination.
+  pub fn required(mut self, features: &[&str]) -> Self {
+    self.required.extend(
+      features
+        .iter()
+        .copied()
+        .map(MiniStr::from),
+    );
+    self
+  }
+
+  /// Adds a group of mutually exclusive features: each combination contains
+  /// exactly one of them.
+  ///
+  /// An empty group is ignored.
+  pub fn one_of(mut self, features: &[&str]) -> Self {
+    if !features.is_empty() {
+      features
+        .iter()
+        .copied()
+        .map(MiniStr::from)
+        .collect::<Box<_>>()
+        .pipe(|group| self.groups.push(group))
+    }
+    self
+  }
+
+  /// Generates each valid feature list.
+  ///
+  /// The groups are expanded like nested loops (the last group varies
+  /// fastest). Without groups, this yields the required features once.
+  pub fn combinations(&self) -> impl Iterator<Item = Vec<MiniStr>> + '_ {
+    let total = self
+      .groups
+      .iter()
+      .map(|g| g.len())
+      .product::<usize>();
+
+    (0..total).map(move |mut index| {
+      let mut picked = Vec::with_capacity(self.groups.len());
+      for group in self.groups.iter().rev() {
+        picked.push(group[index % group.len()].clone());
+        index /= group.len();
+      }
+
+      self
+        .required
+        .iter()
+        .cloned()
+        .chain(picked.into_iter().rev())
+        .collect()
+    })
+  }
+
+  /// Clones `base` once per combination, with its `features` replaced.
+  pub fn cargo_cmds<'s>(
+    &'s self,
+    base: &'s CargoCmd,
+  ) -> impl Iterator<Item = CargoCmd> + 's {
+    self
+      .combinations()
+      .map(|features| {
+        base
+          .clone()
+          .with_features(features.into_boxed_slice())
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn required_and_exclusive_group() {
+    let set = FeatureSet::default()
+      .required(&["std"])
+      .one_of(&["a", "b", "c"]);
+
+    let combinations = set
+      .combinations()
+      .collect::<Vec<_>>();
+    assert_eq!(combinations, [["std", "a"], ["std", "b"], ["std", "c"]]);
+  }
+
+  #[test]
+  fn multiple_groups() {
+    let set = FeatureSet::default()
+      .one_of(&["x", "y"])
+      .one_of(&[])
+      .one_of(&["1", "2", "3"]);
+
+    let combinations = set
+      .combinations()
+      .collect::<Vec<_>>();
+    assert_eq!(combinations.len(), 6);
+    assert_eq!(combinations[0], ["x", "1"]);
+    assert_eq!(combinations[5], ["y", "3"]);
+
+    assert_eq!(
+      FeatureSet::default()
+        .combinations()
+        .collect::<Vec<_>>(),
+      [Vec::<MiniStr>::new()]
+    );
+  }
+}
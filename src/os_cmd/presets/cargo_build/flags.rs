@@ -1,3 +1,4 @@
+use alloc::{boxed::Box, vec::Vec};
 use core::fmt::Display;
 
 use getset::{Getters, WithSetters};
@@ -14,6 +15,15 @@ pub use code_model::CodeModel;
 mod linker_flavor;
 pub use linker_flavor::LinkerFlavor;
 
+mod lto;
+pub use lto::Lto;
+
+mod strip;
+pub use strip::Strip;
+
+mod split_debuginfo;
+pub use split_debuginfo::SplitDebuginfo;
+
 /// Converts an identifier to a kebab-case key and its corresponding value.
 ///
 /// ```
@@ -28,18 +38,31 @@ pub use linker_flavor::LinkerFlavor;
 macro_rules! ident_to_kebab_kv {
   ($ident:ident) => {
     (
-      stringify!($ident)
-        .chars()
-        .map(|c| match c {
-          '_' => '-',
-          c => c,
-        })
-        .collect::<$crate::os_cmd::MiniStr>(),
+      $crate::os_cmd::presets::cargo_build::flags::to_kebab(stringify!($ident)),
       $ident,
     )
   };
 }
 
+/// Converts a snake_case name into kebab-case, i.e., replaces `_` with `-`.
+///
+/// This is the runtime counterpart of [ident_to_kebab_kv!], e.g., for field
+/// names collected via [generate_struct_arr!](crate::generate_struct_arr).
+///
+/// ```
+/// use testutils::os_cmd::presets::cargo_build::flags::to_kebab;
+///
+/// assert_eq!(to_kebab("link_self_contained"), "link-self-contained");
+/// ```
+pub fn to_kebab(s: &str) -> MiniStr {
+  s.chars()
+    .map(|c| match c {
+      '_' => '-',
+      c => c,
+    })
+    .collect()
+}
+
 /// try_into_mini_arg(flag, value) =>`format_args!({flag}={value})`
 ///
 /// if value.is_empty() => None
@@ -55,6 +78,8 @@ where
 }
 
 #[derive(Debug, Clone, WithSetters, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", get = "pub with_prefix")]
 /// Represents various flags used for configuring Rust compilation.
 ///
@@ -63,6 +88,14 @@ where
 ///   - `Some(false)` => `["-C", "target-feature=-crt-static"]`
 ///   - `None` => `[]`
 ///
+/// - `target_features`: e.g. `["+avx2", "+fma"]`
+///   - Merged with `crt_static` into a single `-C target-feature=...` arg:
+///     `["-C", "target-feature=+crt-static,+avx2,+fma"]`
+///   - `crt_static` always comes first. If it is `Some(_)`, any
+///     `+crt-static`/`-crt-static` entry in `target_features` is dropped, so
+///     the dedicated field takes precedence.
+///   - `[]` and `crt_static: None` => `[]`
+///
 /// - prefer_dynamic
 ///   - `Some(true)` => `["-C", "prefer-dynamic=true"]`
 ///   - `Some(false)` => `["-C", "prefer-dynamic=false"]`
@@ -79,6 +112,26 @@ where
 ///   - Some(u) => `["-C", "codegen-units={u}"]`
 ///   - None => `[]`
 ///
+/// - `opt_level`: "0", "1", "2", "3", "s", "z"
+///   - Some(z) => `["-C", "opt-level=z"]`
+///   - None => `[]`
+///
+/// - `debug_assertions`:
+///   - Some(true) => `["-C", "debug-assertions=true"]`
+///   - Some(false) => `["-C", "debug-assertions=false"]`
+///   - None => `[]`
+///
+/// - `overflow_checks`:
+///   - Some(true) => `["-C", "overflow-checks=on"]`
+///   - Some(false) => `["-C", "overflow-checks=off"]`
+///   - None => `[]`
+///
+/// - `lto`: "off", "thin", "fat"
+///
+/// - `strip`: "none", "debuginfo", "symbols"
+///
+/// - `split_debuginfo`: "off", "packed", "unpacked"
+///
 /// - `native_target_cpu`:
 ///   - Some(true) => `["-C", "target-cpu=native"]`
 ///   - Some(false) => `["-C", "target-cpu=generic"]`
@@ -89,6 +142,7 @@ where
 /// See also: [The rustc book](https://doc.rust-lang.org/rustc/codegen-options/index.html)
 pub struct RustFlags {
   crt_static: Option<bool>,
+  target_features: Box<[MiniStr]>,
   prefer_dynamic: Option<bool>,
   linker: MiniStr,
   linker_flavor: LinkerFlavor,
@@ -96,11 +150,139 @@ pub struct RustFlags {
   relocation_model: RelocationModel,
   code_model: CodeModel,
   codegen_units: Option<usize>,
+  opt_level: Option<MiniStr>,
+  debug_assertions: Option<bool>,
+  overflow_checks: Option<bool>,
+  lto: Lto,
+  strip: Strip,
+  split_debuginfo: SplitDebuginfo,
   native_target_cpu: Option<bool>,
   other_flags: Box<[MiniStr]>,
 }
 
 impl RustFlags {
+  /// Resets the codegen options to their defaults:
+  /// `codegen_units`, `opt_level` (`None`) and `lto` (`Ignore`).
+  pub fn clear_codegen(self) -> Self {
+    Self {
+      codegen_units: None,
+      opt_level: None,
+      lto: Lto::Ignore,
+      ..self
+    }
+  }
+
+  /// Resets the linker options to their defaults:
+  /// `linker` (`""`), `linker_flavor` (`Ignore`) and `link_self_contained`
+  /// (`None`).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::flags::RustFlags;
+  ///
+  /// let flags = RustFlags::default()
+  ///   .with_linker("rust-lld".into())
+  ///   .with_codegen_units(Some(1))
+  ///   .clear_linker()
+  ///   .into_vec();
+  /// assert_eq!(flags, ["-C", "codegen-units=1"]);
+  /// ```
+  pub fn clear_linker(self) -> Self {
+    Self {
+      linker: "".into(),
+      linker_flavor: LinkerFlavor::Ignore,
+      link_self_contained: None,
+      ..self
+    }
+  }
+
+  /// Combines a base (`self`) with an overlay (`other`), e.g., shared flags
+  /// plus target-specific ones.
+  ///
+  /// - Override: every other field is taken from `other` if it is set (i.e.,
+  ///   `Some`, not `Ignore`, or a non-empty `linker`); otherwise, `self`'s
+  ///   value is kept.
+  /// - Concatenate: `target_features` and `other_flags` (`self`'s first).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::flags::RustFlags;
+  ///
+  /// let base = RustFlags::default().with_codegen_units(Some(1));
+  /// let overlay = RustFlags::default().with_linker("rust-lld".into());
+  ///
+  /// assert_eq!(
+  ///   base.merge(overlay).into_vec(),
+  ///   ["-C", "linker=rust-lld", "-C", "codegen-units=1"]
+  /// );
+  /// ```
+  pub fn merge(self, other: RustFlags) -> RustFlags {
+    fn concat(a: Box<[MiniStr]>, b: Box<[MiniStr]>) -> Box<[MiniStr]> {
+      match (a.is_empty(), b.is_empty()) {
+        (_, true) => a,
+        (true, _) => b,
+        _ => a.into_iter().chain(b).collect(),
+      }
+    }
+    /// `other` wins unless it equals the "unset" value.
+    fn pick<T: PartialEq>(base: T, overlay: T, unset: T) -> T {
+      match overlay == unset {
+        true => base,
+        _ => overlay,
+      }
+    }
+
+    Self {
+      crt_static: other
+        .crt_static
+        .or(self.crt_static),
+      target_features: concat(self.target_features, other.target_features),
+      prefer_dynamic: other
+        .prefer_dynamic
+        .or(self.prefer_dynamic),
+      linker: pick(self.linker, other.linker, MiniStr::default()),
+      linker_flavor: pick(
+        self.linker_flavor,
+        other.linker_flavor,
+        LinkerFlavor::Ignore,
+      ),
+      link_self_contained: other
+        .link_self_contained
+        .or(self.link_self_contained),
+      relocation_model: pick(
+        self.relocation_model,
+        other.relocation_model,
+        RelocationModel::Ignore,
+      ),
+      code_model: pick(self.code_model, other.code_model, CodeModel::Ignore),
+      codegen_units: other
+        .codegen_units
+        .or(self.codegen_units),
+      opt_level: other
+        .opt_level
+        .or(self.opt_level),
+      debug_assertions: other
+        .debug_assertions
+        .or(self.debug_assertions),
+      overflow_checks: other
+        .overflow_checks
+        .or(self.overflow_checks),
+      lto: pick(self.lto, other.lto, Lto::Ignore),
+      strip: pick(self.strip, other.strip, Strip::Ignore),
+      split_debuginfo: pick(
+        self.split_debuginfo,
+        other.split_debuginfo,
+        SplitDebuginfo::Ignore,
+      ),
+      native_target_cpu: other
+        .native_target_cpu
+        .or(self.native_target_cpu),
+      other_flags: concat(self.other_flags, other.other_flags),
+    }
+  }
+
   /// Collects flags into a Vec
   ///
   ///
@@ -144,9 +326,40 @@ impl RustFlags {
   ///   ]
   /// );
   /// ```
+  ///
+  /// ### Benchmark profile
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::flags::{Lto, RustFlags};
+  ///
+  /// let flags = RustFlags::default()
+  ///   .with_overflow_checks(true.into())
+  ///   .with_lto(Lto::Thin)
+  ///   .into_vec();
+  ///
+  /// assert_eq!(flags, ["-C", "overflow-checks=on", "-C", "lto=thin"]);
+  /// ```
+  ///
+  /// ### Release size
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::flags::{RustFlags, Strip};
+  ///
+  /// let flags = RustFlags::default()
+  ///   .with_opt_level(Some("z".into()))
+  ///   .with_strip(Strip::Symbols)
+  ///   .into_vec();
+  ///
+  /// assert!(
+  ///   flags
+  ///     .windows(2)
+  ///     .any(|w| w == ["-C", "strip=symbols"])
+  /// );
+  /// ```
   pub fn into_vec(self) -> Vec<MiniStr> {
     let Self {
       crt_static,
+      target_features,
       prefer_dynamic,
       linker,
       linker_flavor,
@@ -154,6 +367,12 @@ impl RustFlags {
       relocation_model,
       code_model,
       codegen_units,
+      opt_level,
+      debug_assertions,
+      overflow_checks,
+      lto,
+      strip,
+      split_debuginfo,
       native_target_cpu,
       other_flags,
     } = self;
@@ -163,20 +382,38 @@ impl RustFlags {
       v.and_then(|b| try_into_mini_arg(k, fmt_compact!("{b}")))
     };
 
-    let crt_static = crt_static.map(|b| {
-      if b { "+" } else { "-" }
-        .pipe(|sym| fmt_compact!("target-feature={sym}crt-static"))
-    });
+    // crt_static + target_features => "target-feature=+crt-static,+avx2"
+    let target_feature = {
+      let is_crt_static =
+        |f: &MiniStr| f.trim_start_matches(['+', '-']) == "crt-static";
+
+      crt_static
+        .map(|b| if b { "+crt-static" } else { "-crt-static" }.into())
+        .into_iter()
+        .chain(
+          target_features
+            .into_iter()
+            .filter(|f| !f.is_empty())
+            .filter(|f| crt_static.is_none() || !is_crt_static(f)),
+        )
+        .collect::<Vec<MiniStr>>()
+        .join(",")
+        .pipe(|features| try_into_mini_arg("target-feature", features))
+    };
 
     let codegen_units = codegen_units
       .and_then(|u| try_into_mini_arg("codegen-units", fmt_compact!("{u}")));
 
+    let overflow_checks = overflow_checks.and_then(|b| {
+      try_into_mini_arg("overflow-checks", if b { "on" } else { "off" })
+    });
+
     let native_target_cpu = native_target_cpu.and_then(|b| {
       try_into_mini_arg("target-cpu", if b { "native" } else { "generic" })
     });
 
     [
-      crt_static,
+      target_feature,
       gen_bool_flag(ident_to_kebab_kv! {prefer_dynamic}),
       try_into_mini_arg("linker", linker),
       linker_flavor.into(),
@@ -184,6 +421,12 @@ impl RustFlags {
       relocation_model.into(),
       code_model.into(),
       codegen_units,
+      opt_level.and_then(|x| try_into_mini_arg("opt-level", x)),
+      gen_bool_flag(ident_to_kebab_kv! {debug_assertions}),
+      overflow_checks,
+      lto.into(),
+      strip.into(),
+      split_debuginfo.into(),
       native_target_cpu,
     ]
     .into_iter()
@@ -192,6 +435,26 @@ impl RustFlags {
     .chain(other_flags)
     .collect()
   }
+
+  /// Returns `("RUSTFLAGS", flags)`, where `flags` is [RustFlags::into_vec()]
+  /// joined by spaces.
+  ///
+  /// Unlike setting the global env, the pair can be applied to a single
+  /// command (e.g., via `Command::env`).
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::cargo_build::flags::RustFlags;
+  ///
+  /// let (key, value) = RustFlags::default()
+  ///   .with_crt_static(false.into())
+  ///   .into_env();
+  ///
+  /// assert_eq!(key, "RUSTFLAGS");
+  /// assert_eq!(value, "-C target-feature=-crt-static");
+  /// ```
+  pub fn into_env(self) -> (MiniStr, MiniStr) {
+    ("RUSTFLAGS".into(), self.into_vec().join(" ").into())
+  }
 }
 
 impl Default for RustFlags {
@@ -200,6 +463,7 @@ impl Default for RustFlags {
   /// ```ignore
   /// RustFlags {
   ///     crt_static: None,
+  ///     target_features: [],
   ///     prefer_dynamic: None,
   ///     linker: "",
   ///     linker_flavor: Ignore,
@@ -207,6 +471,12 @@ impl Default for RustFlags {
   ///     relocation_model: Ignore,
   ///     code_model: Ignore,
   ///     codegen_units: None,
+  ///     opt_level: None,
+  ///     debug_assertions: None,
+  ///     overflow_checks: None,
+  ///     lto: Ignore,
+  ///     strip: Ignore,
+  ///     split_debuginfo: Ignore,
   ///     native_target_cpu: None,
   ///     other_flags: [],
   /// }
@@ -215,6 +485,7 @@ impl Default for RustFlags {
     Self {
       other_flags: Default::default(),
       crt_static: None,
+      target_features: Default::default(),
       linker: "".into(),
       prefer_dynamic: None,
       link_self_contained: None,
@@ -222,6 +493,12 @@ impl Default for RustFlags {
       code_model: Default::default(),
       relocation_model: Default::default(),
       codegen_units: None,
+      opt_level: None,
+      debug_assertions: None,
+      overflow_checks: None,
+      lto: Default::default(),
+      strip: Default::default(),
+      split_debuginfo: Default::default(),
       native_target_cpu: None,
     }
   }
@@ -232,6 +509,13 @@ mod tests {
   use super::*;
   use crate::os_cmd::collect_boxed_ministr_slice;
 
+  #[test]
+  fn snake_to_kebab() {
+    assert_eq!(to_kebab("panic_immediate_abort"), "panic-immediate-abort");
+    assert_eq!(to_kebab("lto"), "lto");
+    assert_eq!(to_kebab(""), "");
+  }
+
   #[ignore]
   #[test]
   fn test_default_flags() {
@@ -239,13 +523,15 @@ mod tests {
     dbg!(&flags);
     assert_eq!(flags.other_flags.len(), 0);
     assert_eq!(flags.crt_static, None);
+    assert_eq!(flags.target_features.len(), 0);
     assert_eq!(flags.prefer_dynamic, None);
     assert_eq!(flags.linker, "");
     assert_eq!(flags.link_self_contained, None);
     assert_eq!(flags.codegen_units, None);
+    assert_eq!(flags.opt_level, None);
+    assert_eq!(flags.debug_assertions, None);
     assert_eq!(flags.native_target_cpu, None);
   }
-  #[ignore]
   #[test]
   fn test_rust_flags_into_vec() {
     let flags = RustFlags::default()
@@ -253,6 +539,8 @@ mod tests {
       .with_crt_static(false.into())
       .with_prefer_dynamic(true.into())
       .with_linker("ldd".into())
+      .with_opt_level(Some("z".into()))
+      .with_debug_assertions(false.into())
       .with_other_flags(
         ["-L", "/lib"]
           .into_iter()
@@ -260,7 +548,108 @@ mod tests {
       )
       .into_vec();
 
-    eprintln!("{}", flags.join(" "))
+    eprintln!("{}", flags.join(" "));
+
+    let has_pair = |v: &str| {
+      flags
+        .windows(2)
+        .any(|w| w == ["-C", v])
+    };
+    assert!(has_pair("opt-level=z"));
+    assert!(has_pair("debug-assertions=false"));
+    assert!(flags.ends_with(&["-L".into(), "/lib".into()]));
+  }
+
+  #[test]
+  fn merge_base_and_overlay() {
+    let base = RustFlags::default()
+      .with_codegen_units(Some(16))
+      .with_lto(Lto::Fat)
+      .with_target_features(["+avx2".into()].into())
+      .with_other_flags(["-Zbase".into()].into());
+    let overlay = RustFlags::default()
+      .with_linker("rust-lld".into())
+      .with_lto(Lto::Thin)
+      .with_target_features(["+fma".into()].into())
+      .with_other_flags(["-Zoverlay".into()].into());
+
+    let merged = base.merge(overlay);
+    assert_eq!(merged.get_linker(), "rust-lld");
+    assert_eq!(merged.get_codegen_units(), &Some(16));
+    assert_eq!(merged.get_lto(), &Lto::Thin);
+    assert_eq!(&**merged.get_target_features(), ["+avx2", "+fma"]);
+    assert_eq!(&**merged.get_other_flags(), ["-Zbase", "-Zoverlay"]);
+  }
+
+  #[test]
+  fn rust_flags_into_env() {
+    let flags = RustFlags::default()
+      .with_opt_level(Some("3".into()))
+      .with_lto(Lto::Fat)
+      .with_other_flags(
+        ["-L", "/lib"]
+          .into_iter()
+          .pipe(collect_boxed_ministr_slice),
+      );
+
+    let (key, value) = flags.clone().into_env();
+    assert_eq!(key, "RUSTFLAGS");
+    assert_eq!(value, flags.into_vec().join(" "));
+  }
+
+  #[test]
+  fn parse_code_model() {
+    assert_eq!("large".parse::<CodeModel>(), Ok(CodeModel::Large));
+
+    let err = "nope"
+      .parse::<CodeModel>()
+      .unwrap_err();
+    assert_eq!(err.value(), "nope");
+    assert!("".parse::<CodeModel>().is_err());
+
+    // From<&str> is still lossy.
+    assert_eq!(CodeModel::from("nope"), CodeModel::Ignore);
+  }
+
+  #[test]
+  fn strip_and_split_debuginfo() {
+    let flags = RustFlags::default()
+      .with_lto(Lto::Fat)
+      .with_strip("debuginfo".into())
+      .with_split_debuginfo(SplitDebuginfo::Packed)
+      .into_vec();
+    assert_eq!(
+      flags,
+      [
+        "-C",
+        "lto=fat",
+        "-C",
+        "strip=debuginfo",
+        "-C",
+        "split-debuginfo=packed"
+      ]
+    );
+  }
+
+  #[test]
+  fn clear_linker_keeps_other_fields() {
+    let flags = RustFlags::default()
+      .with_linker("rust-lld".into())
+      .with_linker_flavor(LinkerFlavor::GNUbinutilsLLVMLLD)
+      .with_link_self_contained(true.into())
+      .with_opt_level(Some("s".into()))
+      .with_crt_static(true.into())
+      .clear_linker();
+
+    assert_eq!(flags.get_linker(), "");
+    assert_eq!(*flags.get_linker_flavor(), LinkerFlavor::Ignore);
+    assert_eq!(flags.get_link_self_contained(), &None);
+    assert_eq!(flags.get_opt_level().as_deref(), Some("s"));
+    assert_eq!(flags.get_crt_static(), &Some(true));
+
+    let flags = flags.clear_codegen();
+    assert_eq!(flags.get_opt_level(), &None);
+    assert_eq!(flags.get_crt_static(), &Some(true));
   }
 
   #[ignore]
@@ -297,4 +686,22 @@ mod tests {
       ]
     );
   }
+
+  #[test]
+  fn test_target_features_merged_with_crt_static() {
+    let features =
+      ["+avx2", "-crt-static", "+fma"].pipe(collect_boxed_ministr_slice);
+
+    let flags = RustFlags::default()
+      .with_crt_static(true.into())
+      .with_target_features(features.clone())
+      .into_vec();
+    assert_eq!(flags, ["-C", "target-feature=+crt-static,+avx2,+fma"]);
+
+    // Without `crt_static`, the list is passed through as-is.
+    let flags = RustFlags::default()
+      .with_target_features(features)
+      .into_vec();
+    assert_eq!(flags, ["-C", "target-feature=+avx2,-crt-static,+fma"]);
+  }
 }
@@ -1,6 +1,9 @@
-use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
+use crate::os_cmd::{
+  MiniStr,
+  presets::cargo_build::{ParseError, flags::try_into_mini_arg},
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// rustc --print code-models
 ///
 /// From the rustc book:
@@ -33,6 +36,18 @@ impl From<&str> for CodeModel {
   }
 }
 
+impl core::str::FromStr for CodeModel {
+  type Err = ParseError;
+
+  /// Like `From<&str>`, but returns an error instead of `Ignore`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match Self::from(s) {
+      Self::Ignore => Err(ParseError::new("code model", s)),
+      x => Ok(x),
+    }
+  }
+}
+
 impl CodeModel {
   /// Converts CodeModel as `&str`
   pub const fn as_str(&self) -> &str {
@@ -1,6 +1,6 @@
 use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// > From the rustc book: This flag controls the linker flavor used by rustc.
 /// > If a linker is given with the -C linker flag, then the linker flavor is
 /// > inferred from the value provided. If no linker is given then the linker
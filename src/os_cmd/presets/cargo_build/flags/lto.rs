@@ -0,0 +1,60 @@
+use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// `-C lto`
+///
+/// From the rustc book:
+///
+/// > This flag controls whether LLVM uses link time optimizations to produce
+/// > better optimized code, using whole-program analysis, at the cost of
+/// > longer linking time.
+pub enum Lto {
+  Off,
+  Thin,
+  Fat,
+  Ignore,
+}
+
+impl From<&str> for Lto {
+  fn from(value: &str) -> Self {
+    use Lto::*;
+    match value {
+      "off" => Off,
+      "thin" => Thin,
+      "fat" => Fat,
+      _ => Ignore,
+    }
+  }
+}
+
+impl Lto {
+  /// Converts Lto as `&str`
+  pub const fn as_str(&self) -> &str {
+    use Lto::*;
+    match self {
+      Off => "off",
+      Thin => "thin",
+      Fat => "fat",
+      Ignore => "",
+    }
+  }
+}
+
+impl AsRef<str> for Lto {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl From<Lto> for Option<MiniStr> {
+  fn from(lto: Lto) -> Self {
+    try_into_mini_arg("lto", lto)
+  }
+}
+
+impl Default for Lto {
+  /// Default: Ignore
+  fn default() -> Self {
+    Self::Ignore
+  }
+}
@@ -1,6 +1,6 @@
 use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// rustc --print relocation-models
 pub enum RelocationModel {
   Static,
@@ -0,0 +1,59 @@
+use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// `-C split-debuginfo`
+///
+/// From the rustc book:
+///
+/// > This option controls the emission of "split debuginfo" for debug
+/// > information that rustc generates.
+pub enum SplitDebuginfo {
+  Off,
+  Packed,
+  Unpacked,
+  Ignore,
+}
+
+impl From<&str> for SplitDebuginfo {
+  fn from(value: &str) -> Self {
+    use SplitDebuginfo::*;
+    match value {
+      "off" => Off,
+      "packed" => Packed,
+      "unpacked" => Unpacked,
+      _ => Ignore,
+    }
+  }
+}
+
+impl SplitDebuginfo {
+  /// Converts SplitDebuginfo as `&str`
+  pub const fn as_str(&self) -> &str {
+    use SplitDebuginfo::*;
+    match self {
+      Off => "off",
+      Packed => "packed",
+      Unpacked => "unpacked",
+      Ignore => "",
+    }
+  }
+}
+
+impl AsRef<str> for SplitDebuginfo {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl From<SplitDebuginfo> for Option<MiniStr> {
+  fn from(split: SplitDebuginfo) -> Self {
+    try_into_mini_arg("split-debuginfo", split)
+  }
+}
+
+impl Default for SplitDebuginfo {
+  /// Default: Ignore
+  fn default() -> Self {
+    Self::Ignore
+  }
+}
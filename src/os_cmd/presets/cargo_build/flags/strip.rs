@@ -0,0 +1,59 @@
+use crate::os_cmd::{MiniStr, presets::cargo_build::flags::try_into_mini_arg};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// `-C strip`
+///
+/// From the rustc book:
+///
+/// > The option `-C strip=val` controls stripping of debuginfo and similar
+/// > auxiliary data from binaries during linking.
+pub enum Strip {
+  None,
+  Debuginfo,
+  Symbols,
+  Ignore,
+}
+
+impl From<&str> for Strip {
+  fn from(value: &str) -> Self {
+    use Strip::*;
+    match value {
+      "none" => None,
+      "debuginfo" => Debuginfo,
+      "symbols" => Symbols,
+      _ => Ignore,
+    }
+  }
+}
+
+impl Strip {
+  /// Converts Strip as `&str`
+  pub const fn as_str(&self) -> &str {
+    use Strip::*;
+    match self {
+      None => "none",
+      Debuginfo => "debuginfo",
+      Symbols => "symbols",
+      Ignore => "",
+    }
+  }
+}
+
+impl AsRef<str> for Strip {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl From<Strip> for Option<MiniStr> {
+  fn from(strip: Strip) -> Self {
+    try_into_mini_arg("strip", strip)
+  }
+}
+
+impl Default for Strip {
+  /// Default: Ignore
+  fn default() -> Self {
+    Self::Ignore
+  }
+}
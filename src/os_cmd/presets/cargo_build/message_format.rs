@@ -0,0 +1,71 @@
+use crate::os_cmd::{
+  MiniStr,
+  presets::cargo_build::{ArgConverter, try_into_long_arg},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+/// cargo `--message-format`
+///
+/// - Human => `[]` (cargo's default)
+/// - Short => `["--message-format=short"]`
+/// - Json => `["--message-format=json"]`
+/// - JsonDiagnosticShort => `["--message-format=json-diagnostic-short"]`
+///
+/// `Json` variants write one JSON object per line to stdout, which can be
+/// captured via [CommandSpawner](crate::os_cmd::CommandSpawner).
+pub enum MessageFormat {
+  #[default]
+  Human,
+  Short,
+  Json,
+  JsonDiagnosticShort,
+}
+
+impl MessageFormat {
+  /// Converts MessageFormat as `&str` (Human => "")
+  pub const fn as_str(&self) -> &'static str {
+    use MessageFormat::*;
+    match self {
+      Human => "",
+      Short => "short",
+      Json => "json",
+      JsonDiagnosticShort => "json-diagnostic-short",
+    }
+  }
+}
+
+impl AsRef<str> for MessageFormat {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl ArgConverter for MessageFormat {
+  type ArgsIter = core::option::IntoIter<MiniStr>;
+
+  fn to_args(&self) -> Self::ArgsIter {
+    try_into_long_arg("message-format", self).into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn message_format_to_args() {
+    use MessageFormat::*;
+
+    let arg = |f: MessageFormat| f.to_args().next();
+
+    assert_eq!(arg(Human), None);
+    assert_eq!(arg(Short).as_deref(), Some("--message-format=short"));
+    assert_eq!(arg(Json).as_deref(), Some("--message-format=json"));
+    assert_eq!(
+      arg(JsonDiagnosticShort).as_deref(),
+      Some("--message-format=json-diagnostic-short")
+    );
+  }
+}
@@ -0,0 +1,76 @@
+use getset::{CopyGetters, WithSetters};
+
+use crate::os_cmd::{MiniStr, presets::cargo_build::ArgConverter};
+
+#[derive(Debug, Clone, Copy, Default, WithSetters, CopyGetters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[getset(set_with = "pub", get_copy = "pub with_prefix")]
+/// Cargo's manifest/network options, useful for hermetic CI builds.
+///
+/// - `locked`: `true` => `"--locked"` (asserts that `Cargo.lock` is up-to-date)
+/// - `offline`: `true` => `"--offline"` (no network access)
+/// - `frozen`: `true` => `"--frozen"` (equivalent to `--locked --offline`)
+///
+/// By default (`NetworkFlags::default()`), all options are set to `false`.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::{
+///   CargoCmd,
+///   cargo_build::{ArgConverter, NetworkFlags},
+/// };
+///
+/// let flags = NetworkFlags::default()
+///   .with_locked(true)
+///   .with_offline(true);
+///
+/// assert_eq!(
+///   flags
+///     .to_args()
+///     .collect::<Vec<_>>(),
+///   ["--locked", "--offline"]
+/// );
+///
+/// let vec = CargoCmd::default()
+///   .with_network_flags(flags)
+///   .into_vec();
+/// assert_eq!(
+///   vec,
+///   [
+///     "cargo",
+///     "build",
+///     "--locked",
+///     "--offline",
+///     "--profile=release"
+///   ]
+/// );
+/// ```
+pub struct NetworkFlags {
+  locked: bool,
+  offline: bool,
+  frozen: bool,
+}
+
+impl ArgConverter for NetworkFlags {
+  type ArgsIter = core::iter::Flatten<core::array::IntoIter<Option<MiniStr>, 3>>;
+
+  /// Converts the enabled flags into args, in the order
+  /// `--locked`, `--offline`, `--frozen`.
+  fn to_args(&self) -> Self::ArgsIter {
+    let Self {
+      locked,
+      offline,
+      frozen,
+    } = *self;
+
+    [
+      locked.then(|| "--locked".into()),
+      offline.then(|| "--offline".into()),
+      frozen.then(|| "--frozen".into()),
+    ]
+    .into_iter()
+    .flatten()
+  }
+}
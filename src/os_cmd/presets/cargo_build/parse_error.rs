@@ -0,0 +1,48 @@
+use crate::os_cmd::MiniStr;
+
+/// The error of the `FromStr` impls of the preset enums (e.g.,
+/// [CodeModel](super::flags::CodeModel)), for unrecognized input.
+///
+/// Unlike `From<&str>`, which falls back to `Ignore`/`Custom`, `parse()`
+/// rejects unknown values.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::cargo_build::flags::CodeModel;
+///
+/// let err = "huge"
+///   .parse::<CodeModel>()
+///   .unwrap_err();
+/// assert_eq!(err.to_string(), r#"invalid code model: "huge""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+  /// What was parsed, e.g., "code model".
+  kind: &'static str,
+  /// The rejected input.
+  value: MiniStr,
+}
+
+impl ParseError {
+  pub(crate) fn new(kind: &'static str, value: &str) -> Self {
+    Self {
+      kind,
+      value: value.into(),
+    }
+  }
+
+  /// The rejected input.
+  pub fn value(&self) -> &str {
+    &self.value
+  }
+}
+
+impl core::fmt::Display for ParseError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let Self { kind, value } = self;
+    write!(f, "invalid {kind}: {value:?}")
+  }
+}
+
+impl core::error::Error for ParseError {}
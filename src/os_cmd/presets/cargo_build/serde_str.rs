@@ -0,0 +1,45 @@
+//! `serde` support for the string-like enums, via their `as_str()` form
+//! (e.g., `Lto::Thin` <=> `"thin"`, `CargoProfile::Custom("bench")` <=>
+//! `"bench"`).
+//!
+//! Deserialization goes through `From<&str>`, so, like the `From` impls,
+//! unknown values become `Ignore`/`Custom(_)` instead of an error.
+
+use alloc::string::String;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::os_cmd::presets::cargo_build::{
+  CargoProfile, CargoTarget, SubCmd,
+  flags::{CodeModel, LinkerFlavor, Lto, RelocationModel, SplitDebuginfo, Strip},
+};
+
+macro_rules! impl_serde_as_str {
+  ($($ty:ty),+ $(,)?) => {
+    $(
+      impl Serialize for $ty {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          serializer.serialize_str(self.as_str())
+        }
+      }
+
+      impl<'de> Deserialize<'de> for $ty {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+          String::deserialize(deserializer).map(|s| Self::from(s.as_str()))
+        }
+      }
+    )+
+  };
+}
+
+impl_serde_as_str!(
+  SubCmd,
+  CargoProfile,
+  CargoTarget,
+  CodeModel,
+  LinkerFlavor,
+  Lto,
+  RelocationModel,
+  SplitDebuginfo,
+  Strip,
+);
@@ -1,6 +1,6 @@
-use crate::os_cmd::MiniStr;
+use crate::os_cmd::{MiniStr, presets::cargo_build::ParseError};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 /// cargo sub command: e.g., build, run
 pub enum SubCmd {
   #[default]
@@ -13,6 +13,20 @@ pub enum SubCmd {
   Custom(MiniStr),
 }
 
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::cargo_build::{CargoProfile, SubCmd};
+///
+/// let msg = format!("cargo {} --profile={}", SubCmd::Test, CargoProfile::Release);
+/// assert_eq!(msg, "cargo test --profile=release");
+/// ```
+impl core::fmt::Display for SubCmd {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
 impl From<&str> for SubCmd {
   fn from(value: &str) -> Self {
     use SubCmd::*;
@@ -28,6 +42,19 @@ impl From<&str> for SubCmd {
   }
 }
 
+impl core::str::FromStr for SubCmd {
+  type Err = ParseError;
+
+  /// Like `From<&str>`, but only accepts the built-in sub commands. Use
+  /// `From<&str>` for custom ones.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match Self::from(s) {
+      Self::Custom(_) => Err(ParseError::new("cargo sub command", s)),
+      x => Ok(x),
+    }
+  }
+}
+
 impl SubCmd {
   const fn as_static_str(&self) -> &str {
     use SubCmd::*;
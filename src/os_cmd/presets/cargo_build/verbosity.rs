@@ -0,0 +1,70 @@
+use crate::os_cmd::{MiniStr, presets::cargo_build::ArgConverter};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+/// cargo output verbosity
+///
+/// - Quiet => `["-q"]`
+/// - Normal => `[]`
+/// - Verbose => `["-v"]`
+/// - VeryVerbose => `["-vv"]`
+pub enum Verbosity {
+  Quiet,
+  #[default]
+  Normal,
+  Verbose,
+  VeryVerbose,
+}
+
+impl Verbosity {
+  /// Converts Verbosity as `&str` (Normal => "")
+  pub const fn as_str(&self) -> &'static str {
+    use Verbosity::*;
+    match self {
+      Quiet => "-q",
+      Normal => "",
+      Verbose => "-v",
+      VeryVerbose => "-vv",
+    }
+  }
+
+  /// Returns the flag, or `None` for `Normal`.
+  pub const fn as_arg(&self) -> Option<&'static str> {
+    match self {
+      Self::Normal => None,
+      v => Some(v.as_str()),
+    }
+  }
+}
+
+impl AsRef<str> for Verbosity {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl ArgConverter for Verbosity {
+  type ArgsIter = core::option::IntoIter<MiniStr>;
+
+  fn to_args(&self) -> Self::ArgsIter {
+    self
+      .as_arg()
+      .map(MiniStr::const_new)
+      .into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn verbosity_to_args() {
+    let args = |v: Verbosity| v.to_args().collect::<Vec<_>>();
+
+    assert_eq!(args(Verbosity::VeryVerbose), ["-vv"]);
+    assert_eq!(args(Verbosity::Quiet), ["-q"]);
+    assert!(args(Verbosity::Normal).is_empty());
+  }
+}
@@ -0,0 +1,169 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use getset::{Getters, WithSetters};
+use tap::Pipe;
+
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand};
+use crate::os_cmd::{MiniStr, presets::cargo_build::try_into_long_arg};
+#[cfg(feature = "os_cmd")]
+impl RunnableCommand<'_> for CargoClippy {}
+
+#[derive(Debug, Clone, WithSetters, Getters)]
+#[getset(set_with = "pub", get = "pub with_prefix")]
+/// Configurable cargo clippy command.
+///
+/// ```ignore
+/// [
+///   "cargo", "+nightly", "clippy",
+///   "--package={pkg}", // Automatically disabled when pkg is an empty string.
+///   "--all-targets", "--all-features",
+///   extra_args...,
+///   "--", // Only present when there are lint overrides.
+///   "-D", deny[0], "-D", deny[1], ...
+///   "-W", warn[0], ...
+/// ]
+/// ```
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::{collect_boxed_ministr_slice, presets::CargoClippy};
+///
+/// let cmd = CargoClippy::default()
+///   .with_all_targets(true)
+///   .with_deny(collect_boxed_ministr_slice(["warnings"]));
+///
+/// assert_eq!(
+///   cmd.clone().into_vec(),
+///   ["cargo", "clippy", "--all-targets", "--", "-D", "warnings"]
+/// );
+///
+/// # #[cfg(feature = "os_cmd")]
+/// let _command: testutils::os_cmd::CommandRepr = cmd.into();
+/// ```
+pub struct CargoClippy {
+  nightly: bool,
+  pkg: MiniStr,
+  all_targets: bool,
+  all_features: bool,
+  /// Lints to deny: `-D {lint}`
+  deny: Box<[MiniStr]>,
+  /// Lints to warn: `-W {lint}`
+  warn: Box<[MiniStr]>,
+  /// Extra cargo args (placed before `--`).
+  extra_args: Box<[MiniStr]>,
+}
+
+impl Default for CargoClippy {
+  /// Default:
+  ///
+  /// ```ignore
+  /// CargoClippy {
+  ///     nightly: false,
+  ///     pkg: "",
+  ///     all_targets: false,
+  ///     all_features: false,
+  ///     deny: [],
+  ///     warn: [],
+  ///     extra_args: [],
+  /// }
+  /// ```
+  fn default() -> Self {
+    Self {
+      nightly: false,
+      pkg: "".into(),
+      all_targets: false,
+      all_features: false,
+      deny: Default::default(),
+      warn: Default::default(),
+      extra_args: Default::default(),
+    }
+  }
+}
+
+impl CargoClippy {
+  /// Collects all CargoClippy options into a vec
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_vec(self) -> Vec<MiniStr> {
+    let Self {
+      nightly,
+      pkg,
+      all_targets,
+      all_features,
+      deny,
+      warn,
+      extra_args,
+    } = self;
+
+    let has_lints = !(deny.is_empty() && warn.is_empty());
+
+    // lint => ["-D", lint]
+    let lint_args = |level: &'static str, lints: Box<[MiniStr]>| {
+      lints
+        .into_iter()
+        .flat_map(move |lint| [level.into(), lint])
+    };
+
+    MiniStr::const_new("cargo")
+      .pipe(core::iter::once)
+      .chain(nightly.then(|| "+nightly".into()))
+      .chain(["clippy".into()])
+      .chain(try_into_long_arg("package", pkg))
+      .chain(all_targets.then(|| "--all-targets".into()))
+      .chain(all_features.then(|| "--all-features".into()))
+      .chain(extra_args)
+      // The `--` separator is omitted when there are no lint overrides.
+      .chain(has_lints.then(|| "--".into()))
+      .chain(lint_args("-D", deny))
+      .chain(lint_args("-W", warn))
+      .collect()
+  }
+}
+
+#[cfg(feature = "os_cmd")]
+impl From<CargoClippy> for CommandRepr<'_> {
+  fn from(value: CargoClippy) -> Self {
+    value
+      .into_vec()
+      .into_boxed_slice()
+      .pipe(CommandRepr::OwnedSlice)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::os_cmd::collect_boxed_ministr_slice;
+
+  #[test]
+  fn clippy_without_lint_overrides() {
+    let vec = CargoClippy::default()
+      .with_nightly(true)
+      .with_all_features(true)
+      .into_vec();
+    assert_eq!(vec, ["cargo", "+nightly", "clippy", "--all-features"]);
+  }
+
+  #[test]
+  fn clippy_deny_and_warn() {
+    let vec = CargoClippy::default()
+      .with_pkg("testutils".into())
+      .with_deny(["clippy::foo"].pipe(collect_boxed_ministr_slice))
+      .with_warn(["clippy::bar"].pipe(collect_boxed_ministr_slice))
+      .into_vec();
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "clippy",
+        "--package=testutils",
+        "--",
+        "-D",
+        "clippy::foo",
+        "-W",
+        "clippy::bar"
+      ]
+    );
+  }
+}
@@ -1,10 +1,16 @@
+use alloc::boxed::Box;
+
 use getset::{Getters, Setters, WithSetters};
-use tap::Pipe;
 
-use crate::os_cmd::{CommandRepr, RunnableCommand, presets::StrVec};
+use crate::os_cmd::presets::{CfgBuilder, StrVec, cargo_build::Verbosity};
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand};
+#[cfg(feature = "os_cmd")]
 impl<'a> RunnableCommand<'a> for CargoDoc<'a> {}
 
 #[derive(Debug, Clone, WithSetters, Setters, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", set = "pub", get = "pub with_prefix")]
 /// Configurable cargo rustdoc command.
 ///
@@ -16,6 +22,7 @@ impl<'a> RunnableCommand<'a> for CargoDoc<'a> {}
 /// ```ignore
 /// [
 ///   "cargo", "+nightly", "rustdoc",
+///   "-q" | "-v" | "-vv", // Omitted when verbosity is Normal.
 ///   "--package", pkg, // Automatically disables `--package` when pkg is an empty string.
 ///   "--all-features", "--open",
 ///   "--",
@@ -27,13 +34,10 @@ impl<'a> RunnableCommand<'a> for CargoDoc<'a> {}
 /// ## Example
 ///
 /// ```
-/// use testutils::{
-///   get_pkg_name,
-///   os_cmd::{CommandRepr, presets::CargoDoc},
-/// };
+/// use testutils::os_cmd::presets::CargoDoc;
 ///
 /// let cmd = CargoDoc::default()
-///   .with_pkg(get_pkg_name!())
+///   .with_pkg("testutils")
 ///   .with_nightly(true) // default is true
 ///   .with_open(false) // default is true
 ///   .with_enable_private_items(false) // default is true
@@ -44,7 +48,8 @@ impl<'a> RunnableCommand<'a> for CargoDoc<'a> {}
 /// assert!(!cmd.get_open()); // false
 /// assert!(!cmd.get_enable_private_items()); // false
 ///
-/// let _command: CommandRepr = cmd.into();
+/// # #[cfg(feature = "os_cmd")]
+/// let _command: testutils::os_cmd::CommandRepr = cmd.into();
 /// ```
 pub struct CargoDoc<'a> {
   pkg: &'a str,
@@ -53,6 +58,8 @@ pub struct CargoDoc<'a> {
   all_features: bool,
   open: bool,
   enable_private_items: bool,
+  verbosity: Verbosity,
+  #[cfg_attr(feature = "serde", serde(borrow))]
   extra_args: Box<[&'a str]>,
 }
 
@@ -80,28 +87,94 @@ macro_rules! ident_value_pair {
   };
 }
 
-/// - pkg
-///   - "" => `[]`
-///   - value => `["--package", value]`
-///
-/// - custom_cfg
-///   - "" => `[]`
-///   - value => `["--cfg", value]`
-fn concat_tinycfg<'a>(arg_tup: (&str, &'a str)) -> StrVec<'a, 2> {
-  let (field_name, value) = arg_tup;
-
-  let get_arg = || match field_name {
-    "pkg" => "--package",
-    _ => "--cfg",
-  };
+impl<'a> CargoDoc<'a> {
+  /// Same as `CargoDoc::default()`, but disables `--open` when the `CI`
+  /// environment variable is present (there is no browser in CI).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::CargoDoc;
+  ///
+  /// let cmd = CargoDoc::for_ci();
+  /// assert_eq!(cmd.get_open(), &std::env::var_os("CI").is_none());
+  /// ```
+  #[cfg(feature = "std")]
+  pub fn for_ci() -> Self {
+    let in_ci = std::env::var_os("CI").is_some();
+    Self::default().with_open_unless_ci(in_ci)
+  }
 
-  match value {
-    "" => StrVec::new(),
-    v => [get_arg(), v].into(),
+  /// Disables `--open` if `in_ci` is true; otherwise, keeps the current
+  /// value. See also: [CargoDoc::for_ci()]
+  pub fn with_open_unless_ci(self, in_ci: bool) -> Self {
+    match in_ci {
+      true => self.with_open(false),
+      _ => self,
+    }
+  }
+
+  /// Destructures into all fields, in declaration order:
+  ///
+  /// `(pkg, custom_cfg, nightly, all_features, open, enable_private_items,
+  /// verbosity, extra_args)`
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::presets::{CargoDoc, cargo_build::Verbosity};
+  ///
+  /// let (
+  ///   pkg,
+  ///   custom_cfg,
+  ///   nightly,
+  ///   all_features,
+  ///   open,
+  ///   enable_private_items,
+  ///   verbosity,
+  ///   extra_args,
+  /// ) = CargoDoc::default().into_parts();
+  ///
+  /// assert_eq!((pkg, custom_cfg), ("", "docsrs"));
+  /// assert!(nightly && all_features && open && enable_private_items);
+  /// assert_eq!(verbosity, Verbosity::Normal);
+  /// assert!(extra_args.is_empty());
+  /// ```
+  pub fn into_parts(
+    self,
+  ) -> (
+    &'a str,
+    &'a str,
+    bool,
+    bool,
+    bool,
+    bool,
+    Verbosity,
+    Box<[&'a str]>,
+  ) {
+    let Self {
+      pkg,
+      custom_cfg,
+      nightly,
+      all_features,
+      open,
+      enable_private_items,
+      verbosity,
+      extra_args,
+    } = self;
+
+    (
+      pkg,
+      custom_cfg,
+      nightly,
+      all_features,
+      open,
+      enable_private_items,
+      verbosity,
+      extra_args,
+    )
   }
-}
 
-impl<'a> CargoDoc<'a> {
   /// `CargoDoc<'_>` => `TinyVec<[&str; 11]>`
   #[allow(clippy::unnecessary_lazy_evaluations)]
   pub fn into_tinyvec(self) -> StrVec<'a, 11> {
@@ -112,24 +185,27 @@ impl<'a> CargoDoc<'a> {
       all_features,
       open,
       enable_private_items,
+      verbosity,
       extra_args,
     } = self;
 
-    "cargo"
-      .pipe(core::iter::once)
-      .chain(nightly.then(|| "+nightly"))
-      .chain(["rustdoc"])
-      .chain(ident_value_pair!(pkg).pipe(concat_tinycfg))
-      .chain(all_features.then(|| "--all-features"))
-      .chain(open.then(|| "--open"))
-      .chain(["--"])
-      .chain(ident_value_pair!(custom_cfg).pipe(concat_tinycfg))
-      .chain(enable_private_items.then(|| "--document-private-items"))
-      .chain(extra_args)
-      .collect::<StrVec<11>>()
+    CfgBuilder::new()
+      .arg("cargo")
+      .arg_if(nightly, "+nightly")
+      .arg("rustdoc")
+      .args(verbosity.as_arg())
+      .kv("--package", pkg)
+      .arg_if(all_features, "--all-features")
+      .arg_if(open, "--open")
+      .arg("--")
+      .kv("--cfg", custom_cfg)
+      .arg_if(enable_private_items, "--document-private-items")
+      .args(extra_args)
+      .build()
   }
 }
 
+#[cfg(feature = "os_cmd")]
 impl<'a> From<CargoDoc<'a>> for CommandRepr<'a> {
   /// CargoDoc => `CommandRepr::Slice`
   fn from(value: CargoDoc<'a>) -> Self {
@@ -158,6 +234,7 @@ impl Default for CargoDoc<'_> {
   ///     all_features: true,
   ///     open: true,
   ///     enable_private_items: true,
+  ///     verbosity: Normal,
   ///     extra_args: Default::default(),
   /// }
   /// ```
@@ -169,6 +246,7 @@ impl Default for CargoDoc<'_> {
       all_features: true,
       open: true,
       enable_private_items: true,
+      verbosity: Default::default(),
       extra_args: Default::default(),
     }
   }
@@ -176,12 +254,16 @@ impl Default for CargoDoc<'_> {
 
 #[cfg(test)]
 mod tests {
+  use tap::Pipe;
+
   use super::*;
-  use crate::{get_pkg_name, os_cmd::Runner};
 
   #[test]
   #[ignore]
+  #[cfg(feature = "os_cmd")]
   fn test_cargo_doc_cmd() {
+    use crate::{get_pkg_name, os_cmd::Runner};
+
     let cmd = CargoDoc::default().with_pkg(get_pkg_name!());
     // dbg!(&cmd);
     assert_eq!(cmd.pkg, "testutils");
@@ -194,6 +276,56 @@ mod tests {
     let _runner: Runner = cmd_repr.into();
   }
 
+  #[test]
+  fn cargo_doc_verbosity() {
+    let vec = CargoDoc::default()
+      .with_open(false)
+      .with_verbosity(Verbosity::Quiet)
+      .into_tinyvec();
+    assert_eq!(
+      vec.as_slice(),
+      [
+        "cargo",
+        "+nightly",
+        "rustdoc",
+        "-q",
+        "--all-features",
+        "--",
+        "--cfg",
+        "docsrs",
+        "--document-private-items"
+      ]
+    );
+  }
+
+  #[test]
+  fn for_ci_disables_open() {
+    let cmd = CargoDoc::default().with_open_unless_ci(true);
+    assert!(!cmd.get_open());
+    assert!(cmd.get_nightly());
+
+    assert!(
+      CargoDoc::default()
+        .with_open_unless_ci(false)
+        .get_open()
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn preview_cargo_doc_args() {
+    use crate::os_cmd::RunnableCommand;
+
+    let args = CargoDoc::default().preview_args();
+    assert_eq!(args.first().map(|x| x.as_str()), Some("cargo"));
+    assert_eq!(
+      args,
+      CargoDoc::default()
+        .into_tinyvec()
+        .as_slice()
+    );
+  }
+
   #[ignore]
   #[test]
   fn show_cargo_doc_default() {
@@ -1,10 +1,15 @@
 use getset::{CopyGetters, WithSetters};
 use tap::Pipe;
 
-use crate::os_cmd::{CommandRepr, RunnableCommand, presets::StrVec};
+use crate::os_cmd::presets::StrVec;
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand};
+#[cfg(feature = "os_cmd")]
 impl RunnableCommand<'_> for CargoFmt {}
 
 #[derive(Debug, Clone, WithSetters, CopyGetters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[getset(set_with = "pub", get_copy = "pub with_prefix")]
 /// Configurable cargo fmt command.
 ///
@@ -17,7 +22,7 @@ impl RunnableCommand<'_> for CargoFmt {}
 /// ## Example
 ///
 /// ```
-/// use testutils::os_cmd::{CommandRepr, presets::CargoFmt};
+/// use testutils::os_cmd::presets::CargoFmt;
 ///
 /// let cmd = CargoFmt::default()
 ///   .with_nightly(false) // default is true
@@ -25,7 +30,8 @@ impl RunnableCommand<'_> for CargoFmt {}
 /// // dbg!(&cmd);
 /// assert!(!cmd.get_nightly()); // false
 ///
-/// let _command: CommandRepr = cmd.into();
+/// # #[cfg(feature = "os_cmd")]
+/// let _command: testutils::os_cmd::CommandRepr = cmd.into();
 /// ```
 pub struct CargoFmt {
   nightly: bool,
@@ -37,29 +43,34 @@ impl Default for CargoFmt {
   }
 }
 
+impl CargoFmt {
+  /// `CargoFmt` => `TinyVec<[&str; 3]>`
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_tinyvec(self) -> StrVec<'static, 3> {
+    "cargo"
+      .pipe(core::iter::once)
+      .chain(self.nightly.then(|| "+nightly"))
+      .chain(["fmt"])
+      .collect()
+  }
+}
+
+#[cfg(feature = "os_cmd")]
 impl From<CargoFmt> for CommandRepr<'_> {
   /// ```ignore
   /// [
   ///   "cargo", "+nightly", "fmt",
   /// ].into_boxed_slice()
   /// ```
-  #[allow(clippy::unnecessary_lazy_evaluations)]
   fn from(value: CargoFmt) -> Self {
-    "cargo"
-      .pipe(core::iter::once)
-      .chain(
-        value
-          .get_nightly()
-          .then(|| "+nightly"),
-      )
-      .chain(["fmt"])
-      .collect::<StrVec<3>>()
+    value
+      .into_tinyvec()
       .into_boxed_slice()
       .pipe(CommandRepr::Slice)
   }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "os_cmd"))]
 mod tests {
   use super::*;
 
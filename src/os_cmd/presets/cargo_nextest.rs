@@ -0,0 +1,193 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use getset::{Getters, WithSetters};
+use tap::Pipe;
+
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand};
+use crate::os_cmd::{
+  MiniStr, fmt_compact,
+  presets::cargo_build::{CargoTarget, try_into_long_arg},
+};
+#[cfg(feature = "os_cmd")]
+impl RunnableCommand<'_> for CargoNextest {}
+
+#[derive(Debug, Clone, WithSetters, Getters)]
+#[getset(set_with = "pub", get = "pub with_prefix")]
+/// Configurable `cargo nextest run` command.
+///
+/// [cargo-nextest](https://nexte.st) is a third-party subcommand, so it isn't
+/// modeled by `SubCmd`.
+///
+/// ```ignore
+/// [
+///   "cargo", "+nightly", "nextest", "run",
+///   "--package={pkg}", "--workspace", "--target={target}",
+///   "--all-features", "--no-default-features", "--features={features}",
+///   "--retries", "{n}",
+///   "-j", "{n}",
+///   extra_args...,
+///   "--", // Only present when there are test binary args.
+///   test_args...,
+/// ]
+/// ```
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::CargoNextest;
+///
+/// let cmd = CargoNextest::default()
+///   .with_retries(Some(2))
+///   .with_test_threads(Some(4));
+///
+/// assert_eq!(
+///   cmd.clone().into_vec(),
+///   ["cargo", "nextest", "run", "--retries", "2", "-j", "4"]
+/// );
+///
+/// # #[cfg(feature = "os_cmd")]
+/// let _command: testutils::os_cmd::CommandRepr = cmd.into();
+/// ```
+pub struct CargoNextest {
+  nightly: bool,
+  pkg: MiniStr,
+  target: CargoTarget,
+  all_packages: bool,
+  all_features: bool,
+  no_default_features: bool,
+  features: Box<[MiniStr]>,
+  /// `--retries {n}`
+  retries: Option<usize>,
+  /// `-j {n}`
+  test_threads: Option<usize>,
+  /// Extra nextest args (placed before `--`).
+  extra_args: Box<[MiniStr]>,
+  /// Extra test binary args (placed after `--`).
+  test_args: Box<[MiniStr]>,
+}
+
+impl Default for CargoNextest {
+  /// Default:
+  ///
+  /// ```ignore
+  /// CargoNextest {
+  ///     nightly: false,
+  ///     pkg: "",
+  ///     target: default,
+  ///     all_packages: false,
+  ///     all_features: false,
+  ///     no_default_features: false,
+  ///     features: [],
+  ///     retries: None,
+  ///     test_threads: None,
+  ///     extra_args: [],
+  ///     test_args: [],
+  /// }
+  /// ```
+  fn default() -> Self {
+    Self {
+      nightly: false,
+      pkg: "".into(),
+      target: Default::default(),
+      all_packages: false,
+      all_features: false,
+      no_default_features: false,
+      features: Default::default(),
+      retries: None,
+      test_threads: None,
+      extra_args: Default::default(),
+      test_args: Default::default(),
+    }
+  }
+}
+
+impl CargoNextest {
+  /// Collects all CargoNextest options into a vec
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_vec(self) -> Vec<MiniStr> {
+    let Self {
+      nightly,
+      pkg,
+      target,
+      all_packages,
+      all_features,
+      no_default_features,
+      features,
+      retries,
+      test_threads,
+      extra_args,
+      test_args,
+    } = self;
+
+    // (flag, n) => [flag, n]
+    let flag_with_num = |flag: &'static str, num: Option<usize>| {
+      num
+        .into_iter()
+        .flat_map(move |n| [flag.into(), fmt_compact!("{n}")])
+    };
+
+    MiniStr::const_new("cargo")
+      .pipe(core::iter::once)
+      .chain(nightly.then(|| "+nightly".into()))
+      .chain(["nextest".into(), "run".into()])
+      .chain(try_into_long_arg("package", pkg))
+      .chain(all_packages.then(|| "--workspace".into()))
+      .chain(try_into_long_arg("target", target))
+      .chain(all_features.then(|| "--all-features".into()))
+      .chain(no_default_features.then(|| "--no-default-features".into()))
+      .chain(match features {
+        x if x.is_empty() => None,
+        feats => Some(fmt_compact!("--features={}", feats.join(","))),
+      })
+      .chain(flag_with_num("--retries", retries))
+      .chain(flag_with_num("-j", test_threads))
+      .chain(extra_args)
+      // The `--` separator is only needed when there are test binary args.
+      .chain((!test_args.is_empty()).then(|| "--".into()))
+      .chain(test_args)
+      .collect()
+  }
+}
+
+#[cfg(feature = "os_cmd")]
+impl From<CargoNextest> for CommandRepr<'_> {
+  fn from(value: CargoNextest) -> Self {
+    value
+      .into_vec()
+      .into_boxed_slice()
+      .pipe(CommandRepr::OwnedSlice)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::os_cmd::{
+    collect_boxed_ministr_slice, presets::cargo_build::RustcTarget,
+  };
+
+  #[test]
+  fn nextest_with_target_and_features() {
+    let vec = CargoNextest::default()
+      .with_nightly(true)
+      .with_target(RustcTarget::x86_64_unknown_linux_gnu.into())
+      .with_features(["std", "os_cmd"].pipe(collect_boxed_ministr_slice))
+      .with_test_args(["--ignored"].pipe(collect_boxed_ministr_slice))
+      .into_vec();
+
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "+nightly",
+        "nextest",
+        "run",
+        "--target=x86_64-unknown-linux-gnu",
+        "--features=std,os_cmd",
+        "--",
+        "--ignored",
+      ]
+    );
+  }
+}
@@ -0,0 +1,219 @@
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "os_cmd")]
+use std::io;
+
+use getset::{Getters, WithSetters};
+use tap::Pipe;
+
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand, Runner};
+use crate::os_cmd::{MiniStr, fmt_compact, presets::cargo_build::try_into_long_arg};
+
+#[cfg(feature = "os_cmd")]
+impl RunnableCommand<'_> for CargoRun {
+  /// Runs the command, rejecting the `bin` + `example` combination.
+  ///
+  /// See also: [CargoRun::try_into_vec()]
+  fn run(self) -> io::Result<()> {
+    self
+      .try_into_vec()?
+      .pipe(Runner::from)
+      .run_command()
+  }
+}
+
+#[derive(Debug, Clone, WithSetters, Getters)]
+#[getset(set_with = "pub", get = "pub with_prefix")]
+/// Configurable cargo run command.
+///
+/// ```ignore
+/// [
+///   "cargo", "+nightly", "run",
+///   "--package={pkg}", // Automatically disabled when pkg is an empty string.
+///   "--bin={bin}" or "--example={example}",
+///   "--release",
+///   "--features={features}",
+///   extra_args...,
+///   "--", // Only present when there are program args.
+///   args...,
+/// ]
+/// ```
+///
+/// `bin` and `example` are mutually exclusive:
+///
+/// - [CargoRun::try_into_vec()] returns an error if both are set.
+/// - [CargoRun::into_vec()] does not check it: `example` takes precedence.
+///
+/// Only [RunnableCommand::run()] validates (via `try_into_vec()`). The
+/// infallible conversions, i.e., `into_runner()`, `into_spawner()` and
+/// `CommandRepr::from`, silently drop `bin` as `into_vec()` does.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "os_cmd")] {
+/// use testutils::os_cmd::{collect_boxed_ministr_slice, presets::CargoRun};
+///
+/// let vec = CargoRun::default()
+///   .with_release(true)
+///   .with_example(Some("demo".into()))
+///   .with_args(collect_boxed_ministr_slice(["--flag"]))
+///   .try_into_vec()?;
+///
+/// assert_eq!(
+///   vec,
+///   [
+///     "cargo",
+///     "run",
+///     "--example=demo",
+///     "--release",
+///     "--",
+///     "--flag"
+///   ]
+/// );
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct CargoRun {
+  nightly: bool,
+  pkg: MiniStr,
+  /// `--bin={bin}`
+  bin: Option<MiniStr>,
+  /// `--example={example}`
+  example: Option<MiniStr>,
+  /// `--release`
+  release: bool,
+  features: Box<[MiniStr]>,
+  /// Extra cargo args (placed before `--`).
+  extra_args: Box<[MiniStr]>,
+  /// Program args (placed after `--`).
+  args: Box<[MiniStr]>,
+}
+
+impl Default for CargoRun {
+  /// Default:
+  ///
+  /// ```ignore
+  /// CargoRun {
+  ///     nightly: false,
+  ///     pkg: "",
+  ///     bin: None,
+  ///     example: None,
+  ///     release: false,
+  ///     features: [],
+  ///     extra_args: [],
+  ///     args: [],
+  /// }
+  /// ```
+  fn default() -> Self {
+    Self {
+      nightly: false,
+      pkg: "".into(),
+      bin: None,
+      example: None,
+      release: false,
+      features: Default::default(),
+      extra_args: Default::default(),
+      args: Default::default(),
+    }
+  }
+}
+
+impl CargoRun {
+  /// Like [CargoRun::into_vec()], but returns an error of kind
+  /// [InvalidInput](io::ErrorKind::InvalidInput) if both `bin` and `example`
+  /// are set.
+  #[cfg(feature = "os_cmd")]
+  pub fn try_into_vec(self) -> io::Result<Vec<MiniStr>> {
+    match (&self.bin, &self.example) {
+      (Some(_), Some(_)) => io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "cargo run: `bin` and `example` are mutually exclusive",
+      )
+      .pipe(Err),
+      _ => self.into_vec().pipe(Ok),
+    }
+  }
+
+  /// Collects all CargoRun options into a vec
+  ///
+  /// If both `bin` and `example` are set, `example` takes precedence.
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_vec(self) -> Vec<MiniStr> {
+    let Self {
+      nightly,
+      pkg,
+      bin,
+      example,
+      release,
+      features,
+      extra_args,
+      args,
+    } = self;
+
+    // --example={example} or --bin={bin}
+    let target = match (bin, example) {
+      (_, Some(e)) => try_into_long_arg("example", e),
+      (Some(b), _) => try_into_long_arg("bin", b),
+      _ => None,
+    };
+
+    MiniStr::const_new("cargo")
+      .pipe(core::iter::once)
+      .chain(nightly.then(|| "+nightly".into()))
+      .chain(["run".into()])
+      .chain(try_into_long_arg("package", pkg))
+      .chain(target)
+      .chain(release.then(|| "--release".into()))
+      .chain(match features {
+        x if x.is_empty() => None,
+        feats => Some(fmt_compact!("--features={}", feats.join(","))),
+      })
+      .chain(extra_args)
+      // The `--` separator is only needed when there are program args.
+      .chain((!args.is_empty()).then(|| "--".into()))
+      .chain(args)
+      .collect()
+  }
+}
+
+#[cfg(feature = "os_cmd")]
+impl From<CargoRun> for CommandRepr<'_> {
+  fn from(value: CargoRun) -> Self {
+    value
+      .into_vec()
+      .into_boxed_slice()
+      .pipe(CommandRepr::OwnedSlice)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cargo_run_bin_without_args() {
+    let vec = CargoRun::default()
+      .with_pkg("testutils".into())
+      .with_bin(Some("app".into()))
+      .into_vec();
+    assert_eq!(vec, ["cargo", "run", "--package=testutils", "--bin=app"]);
+  }
+
+  #[test]
+  #[cfg(feature = "os_cmd")]
+  fn cargo_run_rejects_bin_and_example() {
+    let cmd = CargoRun::default()
+      .with_bin(Some("app".into()))
+      .with_example(Some("demo".into()));
+
+    let err = cmd
+      .clone()
+      .try_into_vec()
+      .expect_err("bin + example should be rejected");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // into_vec(): `example` takes precedence
+    assert_eq!(cmd.into_vec(), ["cargo", "run", "--example=demo"]);
+  }
+}
@@ -0,0 +1,237 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use getset::{Getters, WithSetters};
+use tap::Pipe;
+
+#[cfg(feature = "os_cmd")]
+use crate::os_cmd::{CommandRepr, RunnableCommand};
+use crate::os_cmd::{
+  MiniStr, fmt_compact,
+  presets::cargo_build::{CargoTarget, try_into_long_arg},
+};
+#[cfg(feature = "os_cmd")]
+impl RunnableCommand<'_> for CargoTest {}
+
+#[derive(Debug, Clone, WithSetters, Getters)]
+#[getset(set_with = "pub", get = "pub with_prefix")]
+/// Configurable cargo test command.
+///
+/// Shares the package/target/feature handling with
+/// [CargoCmd](super::CargoCmd), and adds a few test-specific options.
+///
+/// ```ignore
+/// [
+///   "cargo", "+nightly", "test",
+///   "--package={pkg}", "--workspace", "--target={target}",
+///   "--all-features", "--no-default-features", "--features={features}",
+///   "--no-run",
+///   extra_args...,
+///   "--", // Only present when there are libtest args.
+///   "--test-threads={n}", "--nocapture",
+///   test_args...,
+/// ]
+/// ```
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::{collect_boxed_ministr_slice, presets::CargoTest};
+///
+/// let vec = CargoTest::default()
+///   .with_all_packages(true)
+///   .with_features(collect_boxed_ministr_slice(["std", "os_cmd"]))
+///   .with_test_threads(Some(1))
+///   .with_nocapture(true)
+///   .into_vec();
+///
+/// assert_eq!(
+///   vec,
+///   [
+///     "cargo",
+///     "test",
+///     "--workspace",
+///     "--features=std,os_cmd",
+///     "--",
+///     "--test-threads=1",
+///     "--nocapture"
+///   ]
+/// );
+/// ```
+pub struct CargoTest {
+  nightly: bool,
+  cargo: MiniStr,
+  pkg: MiniStr,
+  target: CargoTarget,
+  all_packages: bool,
+  all_features: bool,
+  no_default_features: bool,
+  features: Box<[MiniStr]>,
+  /// `--no-run`: Compile, but don't run tests.
+  no_run: bool,
+  /// `-- --test-threads={n}`
+  test_threads: Option<usize>,
+  /// `-- --nocapture`
+  nocapture: bool,
+  /// Extra cargo args (placed before `--`).
+  extra_args: Box<[MiniStr]>,
+  /// Extra libtest args (placed after `--`).
+  test_args: Box<[MiniStr]>,
+}
+
+impl Default for CargoTest {
+  /// Default:
+  ///
+  /// ```ignore
+  /// CargoTest {
+  ///     nightly: false,
+  ///     cargo: "cargo",
+  ///     pkg: "",
+  ///     target: default,
+  ///     all_packages: false,
+  ///     all_features: false,
+  ///     no_default_features: false,
+  ///     features: [],
+  ///     no_run: false,
+  ///     test_threads: None,
+  ///     nocapture: false,
+  ///     extra_args: [],
+  ///     test_args: [],
+  /// }
+  /// ```
+  fn default() -> Self {
+    Self {
+      nightly: false,
+      cargo: "cargo".into(),
+      pkg: "".into(),
+      target: Default::default(),
+      all_packages: false,
+      all_features: false,
+      no_default_features: false,
+      features: Default::default(),
+      no_run: false,
+      test_threads: None,
+      nocapture: false,
+      extra_args: Default::default(),
+      test_args: Default::default(),
+    }
+  }
+}
+
+impl CargoTest {
+  /// Collects all CargoTest options into a vec
+  #[allow(clippy::unnecessary_lazy_evaluations)]
+  pub fn into_vec(self) -> Vec<MiniStr> {
+    let Self {
+      nightly,
+      cargo,
+      pkg,
+      target,
+      all_packages,
+      all_features,
+      no_default_features,
+      features,
+      no_run,
+      test_threads,
+      nocapture,
+      extra_args,
+      test_args,
+    } = self;
+
+    // libtest args: --test-threads={n} --nocapture ...
+    let libtest_args = test_threads
+      .map(|n| fmt_compact!("--test-threads={n}"))
+      .into_iter()
+      .chain(nocapture.then(|| "--nocapture".into()))
+      .chain(test_args)
+      .collect::<Vec<MiniStr>>();
+
+    match cargo {
+      c if c.is_empty() => "cargo".into(),
+      c => c,
+    }
+    .pipe(core::iter::once)
+    .chain(nightly.then(|| "+nightly".into()))
+    .chain(["test".into()])
+    .chain(try_into_long_arg("package", pkg))
+    .chain(all_packages.then(|| "--workspace".into()))
+    .chain(try_into_long_arg("target", target))
+    .chain(all_features.then(|| "--all-features".into()))
+    .chain(no_default_features.then(|| "--no-default-features".into()))
+    .chain(match features {
+      x if x.is_empty() => None,
+      feats => Some(fmt_compact!("--features={}", feats.join(","))),
+    })
+    .chain(no_run.then(|| "--no-run".into()))
+    .chain(extra_args)
+    // The `--` separator is only needed when there are libtest args.
+    .chain((!libtest_args.is_empty()).then(|| "--".into()))
+    .chain(libtest_args)
+    .collect()
+  }
+}
+
+#[cfg(feature = "os_cmd")]
+impl From<CargoTest> for CommandRepr<'_> {
+  fn from(value: CargoTest) -> Self {
+    value
+      .into_vec()
+      .into_boxed_slice()
+      .pipe(CommandRepr::OwnedSlice)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::os_cmd::{
+    collect_boxed_ministr_slice, presets::cargo_build::RustcTarget,
+  };
+
+  #[test]
+  fn cargo_test_without_libtest_args() {
+    let vec = CargoTest::default()
+      .with_target(RustcTarget::aarch64_linux_android.into())
+      .with_no_default_features(true)
+      .with_no_run(true)
+      .into_vec();
+
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "test",
+        "--target=aarch64-linux-android",
+        "--no-default-features",
+        "--no-run",
+      ]
+    );
+  }
+
+  #[test]
+  fn cargo_test_separator_before_libtest_args() {
+    let vec = CargoTest::default()
+      .with_nightly(true)
+      .with_pkg("testutils".into())
+      .with_extra_args(["--lib"].pipe(collect_boxed_ministr_slice))
+      .with_test_threads(Some(4))
+      .with_test_args(["--ignored"].pipe(collect_boxed_ministr_slice))
+      .into_vec();
+
+    assert_eq!(
+      vec,
+      [
+        "cargo",
+        "+nightly",
+        "test",
+        "--package=testutils",
+        "--lib",
+        "--",
+        "--test-threads=4",
+        "--ignored",
+      ]
+    );
+
+    #[cfg(feature = "os_cmd")]
+    let _runner: crate::os_cmd::Runner = CargoTest::default().into();
+  }
+}
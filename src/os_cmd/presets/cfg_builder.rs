@@ -0,0 +1,109 @@
+use crate::os_cmd::presets::StrVec;
+
+/// A small builder for assembling an argv ([StrVec]) step by step.
+///
+/// The presets mostly collect their args via iterator chains; this is the
+/// same logic for user code that builds argv conditionally.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::presets::CfgBuilder;
+///
+/// let release = true;
+/// let pkg = "";
+///
+/// let argv = CfgBuilder::<6>::new()
+///   .arg("cargo")
+///   .arg("build")
+///   .arg_if(release, "--release")
+///   .kv("--package", pkg) // skipped: empty value
+///   .kv("--target", "x86_64-unknown-linux-gnu")
+///   .build();
+///
+/// assert_eq!(
+///   argv.as_slice(),
+///   [
+///     "cargo",
+///     "build",
+///     "--release",
+///     "--target",
+///     "x86_64-unknown-linux-gnu"
+///   ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CfgBuilder<'a, const N: usize> {
+  argv: StrVec<'a, N>,
+}
+
+impl<'a, const N: usize> CfgBuilder<'a, N> {
+  /// Creates an empty builder.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Appends a single arg.
+  pub fn arg(mut self, arg: &'a str) -> Self {
+    self.argv.push(arg);
+    self
+  }
+
+  /// Appends `arg` only when `cond` is true.
+  pub fn arg_if(self, cond: bool, arg: &'a str) -> Self {
+    match cond {
+      true => self.arg(arg),
+      _ => self,
+    }
+  }
+
+  /// Appends all args from the iterator.
+  pub fn args<I: IntoIterator<Item = &'a str>>(mut self, iter: I) -> Self {
+    self.argv.extend(iter);
+    self
+  }
+
+  /// Appends `[flag, value]`, unless `value` is empty.
+  ///
+  /// - `("--package", "")` => `[]`
+  /// - `("--package", "foo")` => `["--package", "foo"]`
+  pub fn kv(self, flag: &'a str, value: &'a str) -> Self {
+    match value {
+      "" => self,
+      v => self.args([flag, v]),
+    }
+  }
+
+  /// Returns the collected argv.
+  pub fn build(self) -> StrVec<'a, N> {
+    self.argv
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn conditional_args() {
+    let argv = CfgBuilder::<4>::new()
+      .arg("cargo")
+      .arg_if(false, "+nightly")
+      .arg("doc")
+      .arg_if(true, "--open")
+      .build();
+    assert_eq!(argv.as_slice(), ["cargo", "doc", "--open"]);
+  }
+
+  #[test]
+  fn kv_skips_empty_value() {
+    let argv = CfgBuilder::<2>::new()
+      .kv("--package", "")
+      .kv("--cfg", "docsrs")
+      .kv("--features", "")
+      // Spills onto the heap once N is exceeded.
+      .args(["--", "-D", "warnings"])
+      .build();
+    assert_eq!(argv.as_slice(), ["--cfg", "docsrs", "--", "-D", "warnings"]);
+  }
+}
@@ -1,9 +1,13 @@
 use alloc::borrow::Cow;
 use std::{
-  ffi::OsStr,
-  io::{self, Write},
-  path::PathBuf,
-  process::{Child, Command, Stdio},
+  ffi::{OsStr, OsString},
+  fs,
+  io::{self, BufRead, Read, Write},
+  path::{Path, PathBuf},
+  process::{Child, ChildStdin, Command, ExitStatus, Output, Stdio},
+  sync::mpsc,
+  thread,
+  time::{Duration, Instant},
 };
 
 use getset::{Getters, Setters, WithSetters};
@@ -11,7 +15,7 @@ use tap::Pipe;
 
 use crate::{
   bool_ext::BoolExt,
-  os_cmd::{DecodedText, MiniStr, Runner},
+  os_cmd::{DecodedText, MiniStr, OsCmdError, Runner, SpawnGuard},
 };
 
 pub type CowOsStrVec<'a, const N: usize> = tinyvec::TinyVec<[Cow<'a, OsStr>; N]>;
@@ -22,17 +26,86 @@ fn err_invalid_input(msg: &str) -> io::Error {
 }
 
 fn err_empty_command() -> io::Error {
-  err_invalid_input("empty command argv")
+  OsCmdError::EmptyCommand.into()
 }
 
-pub(crate) fn err_failed_to_run(program: Option<&OsStr>) -> io::Error {
-  format!("Failed to run command: {program:?}") //
-    .pipe(io::Error::other)
+/// Wraps a spawn failure together with the program name.
+pub(crate) fn err_spawn_failed(
+  program: &OsStr,
+) -> impl FnOnce(io::Error) -> io::Error {
+  move |source| {
+    OsCmdError::SpawnFailed {
+      program: program.to_os_string(),
+      source,
+    }
+    .into()
+  }
+}
+
+/// Appends `(key, value)` pairs to an optional env list.
+pub(crate) fn extend_env_vars<I, K, V>(
+  envs: Option<Box<[(MiniStr, MiniStr)]>>,
+  iter: I,
+) -> Option<Box<[(MiniStr, MiniStr)]>>
+where
+  I: IntoIterator<Item = (K, V)>,
+  K: Into<MiniStr>,
+  V: Into<MiniStr>,
+{
+  envs
+    .into_iter()
+    .flatten()
+    .chain(
+      iter
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into())),
+    )
+    .collect::<Box<[_]>>()
+    .pipe(|x| (!x.is_empty()).then_some(x))
+}
+
+/// Keeps the `(key, value)` pairs whose key starts with `prefix`.
+///
+/// Non-UTF-8 keys never match.
+pub(crate) fn filter_vars_by_prefix<'p, I>(
+  vars: I,
+  prefix: &'p str,
+) -> impl Iterator<Item = (OsString, OsString)> + 'p
+where
+  I: IntoIterator<Item = (OsString, OsString)>,
+  I::IntoIter: 'p,
+{
+  vars
+    .into_iter()
+    .filter(move |(k, _)| {
+      k.to_str()
+        .is_some_and(|k| k.starts_with(prefix))
+    })
+}
+
+pub(crate) fn err_failed_to_run(program: OsString, status: ExitStatus) -> io::Error {
+  OsCmdError::NonZeroExit { program, status }.into()
 }
 
 /// Runs an OS command without capturing stdout/stderr (inherits the parent's
 /// stdio).
 pub fn run_os_cmd<I>(into_iter: I) -> io::Result<()>
+where
+  I: IntoIterator,
+  I::Item: AsRef<OsStr>,
+{
+  let (program, status) = run_os_cmd_status(into_iter)?;
+
+  status
+    .success() // Convert status to bool
+    .then_ok_or_else(|| err_failed_to_run(program, status)) // Convert bool to Result
+}
+
+/// Same as [run_os_cmd], but returns `(program, exit_status)` instead of
+/// treating a non-zero exit status as an error.
+pub(crate) fn run_os_cmd_status<I>(
+  into_iter: I,
+) -> io::Result<(OsString, ExitStatus)>
 where
   I: IntoIterator,
   I::Item: AsRef<OsStr>,
@@ -45,17 +118,20 @@ where
     .as_ref()
     .to_os_string();
 
-  let failed_to_run = || err_failed_to_run(Some(&program));
-
   Command::new(&program) // Main command creation
     .args(iter) // Remainder as arguments
-    .status()? // Execute and get status
-    .success() // Convert status to bool
-    .then_ok_or_else(failed_to_run) // Convert bool to Result
+    .status() // Execute and get status
+    .map_err(err_spawn_failed(&program))
+    .map(|status| (program, status))
 }
 
 /// How to wire a stdio stream for the child process.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+///
+/// Since `File`/`Append` need to open a file, converting a mode into
+/// [Stdio] is fallible (see [StdioMode::into_input_stdio] and
+/// [StdioMode::into_output_stdio]). [CommandSpawner::spawn] performs this
+/// conversion and propagates the I/O error.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum StdioMode {
   /// Inherit from current process.
   #[default]
@@ -64,19 +140,83 @@ pub enum StdioMode {
   Piped,
   /// Redirect to null device.
   Null,
+  /// Redirect to a file.
+  ///
+  /// - For stdin: the file is opened read-only.
+  /// - For stdout/stderr: the file is created (or truncated).
+  File(PathBuf),
+  /// Like `File`, but stdout/stderr are appended to the end of the file
+  /// instead of truncating it.
+  Append(PathBuf),
 }
 
-impl From<StdioMode> for Stdio {
-  fn from(val: StdioMode) -> Self {
+impl StdioMode {
+  /// Converts the mode into a [Stdio] suitable for the child's stdin.
+  ///
+  /// `File` and `Append` both open the file read-only.
+  pub fn into_input_stdio(self) -> io::Result<Stdio> {
+    use StdioMode::*;
+    match self {
+      File(p) | Append(p) => fs::File::open(p)?.into(),
+      x => x.into_plain_stdio(),
+    }
+    .pipe(Ok)
+  }
+
+  /// Converts the mode into a [Stdio] suitable for the child's stdout/stderr.
+  ///
+  /// - `File`: creates the file, truncating it if it already exists.
+  /// - `Append`: creates the file if needed, and appends to it.
+  pub fn into_output_stdio(self) -> io::Result<Stdio> {
     use StdioMode::*;
-    match val {
-      Inherit => Stdio::inherit(),
+    match self {
+      File(p) => open_output_file(&p, false)?.into(),
+      Append(p) => open_output_file(&p, true)?.into(),
+      x => x.into_plain_stdio(),
+    }
+    .pipe(Ok)
+  }
+
+  /// Converts the stdout and stderr modes together.
+  ///
+  /// If both redirect to the same file with the same mode, the file is opened
+  /// only once and the handle is shared, so `File` does not truncate it twice
+  /// and the two streams do not overwrite each other.
+  fn into_output_stdio_pair(
+    stdout: Self,
+    stderr: Self,
+  ) -> io::Result<(Stdio, Stdio)> {
+    use StdioMode::*;
+    match (&stdout, &stderr) {
+      (File(a), File(b)) | (Append(a), Append(b)) if a == b => {
+        let file = open_output_file(a, matches!(stdout, Append(_)))?;
+        Ok((file.try_clone()?.into(), file.into()))
+      }
+      _ => Ok((stdout.into_output_stdio()?, stderr.into_output_stdio()?)),
+    }
+  }
+
+  /// Infallible conversion for the variants that don't touch the filesystem.
+  fn into_plain_stdio(self) -> Stdio {
+    use StdioMode::*;
+    match self {
       Piped => Stdio::piped(),
       Null => Stdio::null(),
+      _ => Stdio::inherit(),
     }
   }
 }
 
+/// Opens a redirect target for stdout/stderr, truncating it unless `append`.
+fn open_output_file(path: &Path, append: bool) -> io::Result<fs::File> {
+  fs::OpenOptions::new()
+    .create(true)
+    .write(true)
+    .append(append)
+    .truncate(!append)
+    .open(path)
+}
+
 /// `CommandSpawner` is a small builder that treats an iterator as an
 /// `argv`-like sequence:
 ///
@@ -90,10 +230,10 @@ impl From<StdioMode> for Stdio {
 ///
 /// # Notes
 ///
-/// - If you pipe **both** stdin (and write a lot of data) **and** pipe
-///   stdout/stderr, be aware of potential deadlocks if the child writes enough
-///   output to fill its pipe buffer while the parent is blocked writing stdin.
-///   For large payloads, consider writing stdin from another thread while
+/// - [spawn](CommandSpawner::spawn) writes `stdin_data` synchronously. If you
+///   also pipe stdout/stderr and read them only afterwards, a child that fills
+///   its output pipe while the parent is still writing stdin deadlocks. The
+///   `capture_*` methods avoid this by writing stdin from another thread while
 ///   concurrently reading output.
 #[derive(Debug, Clone, PartialEq, Eq, WithSetters, Setters, Getters)]
 #[getset(set = "pub", set_with = "pub", get = "pub with_prefix")]
@@ -116,8 +256,51 @@ pub struct CommandSpawner<'a> {
   /// environment variables
   envs: Option<Box<[(MiniStr, MiniStr)]>>,
 
+  /// Clears the inherited environment before applying `envs`.
+  env_clear: bool,
+
+  /// When set, the child only inherits the parent's environment variables
+  /// whose name starts with this prefix (e.g., `CARGO_`), i.e., the
+  /// environment is cleared, then the matching vars are re-added at spawn.
+  /// `envs` are applied afterwards.
+  ///
+  /// Note: `PATH` is filtered out as well (unless it matches), so prefer an
+  /// absolute program path.
+  env_allowlist_prefix: Option<MiniStr>,
+
   /// working directory for the child process.
   working_dir: Option<PathBuf>,
+
+  /// Upper bound for waiting on the child in the `capture_*` methods.
+  ///
+  /// When it elapses, the child is killed and an error of kind
+  /// [TimedOut](io::ErrorKind::TimedOut) is returned. The deadline also
+  /// bounds draining stdout/stderr, e.g., when a background process spawned
+  /// by the child keeps the pipes open (that process is not killed).
+  /// `spawn()` itself doesn't wait, so it ignores this value.
+  timeout: Option<Duration>,
+
+  /// Upper bound (in bytes) for each captured stream in the `capture_*`
+  /// methods.
+  ///
+  /// Once a stream exceeds it, its pipe is closed (the child then typically
+  /// exits on `SIGPIPE`/`EPIPE`), and:
+  ///
+  /// - an error of kind [Other](io::ErrorKind::Other) is returned, or
+  /// - if `truncate_capture` is true, the output is truncated to
+  ///   `max_capture_bytes`.
+  ///
+  /// For children that ignore a broken pipe, combine it with `timeout`.
+  max_capture_bytes: Option<usize>,
+
+  /// See `max_capture_bytes`.
+  truncate_capture: bool,
+
+  /// Windows only: spawns the child with `CREATE_NO_WINDOW`, so console
+  /// programs don't pop up a window (e.g., from a GUI test harness).
+  ///
+  /// This is a no-op on other platforms.
+  no_window: bool,
 }
 
 impl<'a> Default for CommandSpawner<'a> {
@@ -131,7 +314,13 @@ impl<'a> Default for CommandSpawner<'a> {
   ///   argv: Default::default(),
   ///   stdin_data: None,
   ///   envs: None,
+  ///   env_clear: false,
+  ///   env_allowlist_prefix: None,
   ///   working_dir: None,
+  ///   timeout: None,
+  ///   max_capture_bytes: None,
+  ///   truncate_capture: false,
+  ///   no_window: false,
   /// }
   /// ```
   fn default() -> Self {
@@ -143,7 +332,13 @@ impl<'a> Default for CommandSpawner<'a> {
       argv: Default::default(),
       stdin_data: None,
       envs: None,
+      env_clear: false,
+      env_allowlist_prefix: None,
       working_dir: None,
+      timeout: None,
+      max_capture_bytes: None,
+      truncate_capture: false,
+      no_window: false,
     }
   }
 }
@@ -162,7 +357,7 @@ impl<'a> CommandSpawner<'a> {
     match (has_data, stdin) {
       (true, _) => Piped,
       // (false, Piped) => Inherit,
-      _ => stdin,
+      (_, x) => x,
     }
   }
 
@@ -171,11 +366,36 @@ impl<'a> CommandSpawner<'a> {
   /// ## Errors
   ///
   /// - Returns an error if `command` is `None`.
-  /// - Returns an error if the iterator is empty (no program).
-  /// - Propagates any I/O error from `Command::spawn`.
+  /// - Returns [OsCmdError::EmptyCommand] if the iterator is empty (no
+  ///   program).
+  /// - Wraps any I/O error from `Command::spawn` in [OsCmdError::SpawnFailed].
   /// - If `stdin_data` is set, returns an error if `stdin` is not available
   ///   (e.g., misconfigured to not be piped).
+  /// - Returns an error if a [StdioMode::File] / [StdioMode::Append] path
+  ///   cannot be opened.
   pub fn spawn(self) -> io::Result<Child> {
+    let (mut command, stdin_data) = self.into_command()?;
+
+    command
+      .spawn()
+      .map_err(err_spawn_failed(command.get_program()))?
+      // Optionally write stdin data, then return the (possibly modified) child.
+      .pipe(|child| Self::write_child_stdin(child, stdin_data))
+  }
+
+  /// Like [Self::spawn()], but wraps the child in a [SpawnGuard], which kills
+  /// the process on drop unless [SpawnGuard::into_inner()] is called.
+  pub fn spawn_guarded(self) -> io::Result<SpawnGuard> {
+    self
+      .spawn()
+      .map(SpawnGuard::new)
+  }
+
+  /// Builds the `std::process::Command` without spawning it.
+  ///
+  /// Returns the command together with the pending `stdin_data`, which the
+  /// caller is responsible for writing once the child has been spawned.
+  pub(crate) fn into_command(self) -> io::Result<(Command, Option<&'a [u8]>)> {
     let Self {
       argv: command,
       stdin_data,
@@ -183,41 +403,138 @@ impl<'a> CommandSpawner<'a> {
       stdout: stdout_mode,
       stderr: stderr_mode,
       envs: environment_vars,
+      env_clear,
+      env_allowlist_prefix,
       working_dir,
+      no_window,
       ..
     } = self;
 
-    let stdin_mode = Self::effective_stdin_mode(stdin_data.is_some(), stdin);
+    // Opening redirect files can fail, so convert the modes up front.
+    let stdin_mode =
+      Self::effective_stdin_mode(stdin_data.is_some(), stdin).into_input_stdio()?;
+    let (stdout_mode, stderr_mode) =
+      StdioMode::into_output_stdio_pair(stdout_mode, stderr_mode)?;
 
-    command
-      .into_iter()
-      .pipe(|mut iter| {
-        // Split into (program, remaining args).
-        iter
-          .next()
-          .ok_or_else(err_empty_command)
-          .map(|prog| (prog, iter))
-      })?
-      .pipe(|(prog, iter)| {
-        // Build and spawn the process without going through a shell.
-        prog
-          .pipe(Command::new)
-          .args(iter)
-          .stdin(stdin_mode)
-          .stdout(stdout_mode)
-          .stderr(stderr_mode)
-          .pipe(|x| match environment_vars {
-            Some(map) => x.envs(map),
-            _ => x,
-          })
-          .pipe(|x| match working_dir {
-            Some(p) => x.current_dir(p),
-            _ => x,
-          })
-          .spawn()
-      })?
-      // Optionally write stdin data, then return the (possibly modified) child.
-      .pipe(|child| Self::write_child_stdin(child, stdin_data))
+    let mut iter = command.into_iter();
+    // Split into (program, remaining args).
+    let prog = iter
+      .next()
+      .ok_or_else(err_empty_command)?;
+
+    // Build the process without going through a shell.
+    let mut cmd = Command::new(prog);
+    cmd
+      .args(iter)
+      .stdin(stdin_mode)
+      .stdout(stdout_mode)
+      .stderr(stderr_mode)
+      .pipe(|x| match env_clear {
+        true => x.env_clear(),
+        _ => x,
+      })
+      .pipe(|x| match env_allowlist_prefix {
+        Some(prefix) => x
+          .env_clear()
+          .envs(filter_vars_by_prefix(std::env::vars_os(), &prefix)),
+        _ => x,
+      })
+      .pipe(|x| match environment_vars {
+        Some(map) => x.envs(map),
+        _ => x,
+      })
+      .pipe(|x| match working_dir {
+        Some(p) => x.current_dir(p),
+        _ => x,
+      });
+
+    #[cfg(windows)]
+    if no_window {
+      use std::os::windows::process::CommandExt;
+      const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+      cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    #[cfg(not(windows))]
+    let _ = no_window;
+
+    Ok((cmd, stdin_data))
+  }
+
+  /// Sets stdin, stdout and stderr to the same mode.
+  ///
+  /// Note: with `File(path)`/`Append(path)`, stdin reads the same file that
+  /// stdout/stderr write to. For `File`, the file is truncated before the
+  /// child starts, so stdin is empty. To redirect the output only, use
+  /// [Self::with_out_err()] instead.
+  pub fn with_stdio(self, mode: StdioMode) -> Self {
+    self
+      .with_stdin(mode.clone())
+      .with_out_err(mode)
+  }
+
+  /// Sets stdout and stderr to the same mode, leaving stdin untouched, e.g.,
+  /// `Null` to silence a noisy command.
+  ///
+  /// With `File(path)`/`Append(path)`, the file is opened once and shared by
+  /// both streams, like `> path 2>&1` in a shell.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::{
+  ///   os_cmd::{CommandSpawner, StdioMode},
+  ///   tap::Pipe,
+  /// };
+  ///
+  /// let spawner = "cargo build"
+  ///   .pipe(CommandSpawner::from)
+  ///   .with_out_err(StdioMode::Null);
+  /// assert_eq!(spawner.get_stdout(), &StdioMode::Null);
+  /// assert_eq!(spawner.get_stderr(), &StdioMode::Null);
+  /// assert_eq!(spawner.get_stdin(), &StdioMode::Inherit);
+  /// ```
+  pub fn with_out_err(self, mode: StdioMode) -> Self {
+    self
+      .with_stdout(mode.clone())
+      .with_stderr(mode)
+  }
+
+  /// Appends a single environment variable.
+  ///
+  /// Unlike `with_envs`, this keeps the previously configured variables.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// let v = ["sh", "-c", r#"printf "%s" "$GREETING""#]
+  ///   .pipe(CommandSpawner::from)
+  ///   .with_env("GREETING", "hello")
+  ///   .capture_stdout()?;
+  /// assert_eq!(v.data(), "hello");
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn with_env<K, V>(self, key: K, value: V) -> Self
+  where
+    K: Into<MiniStr>,
+    V: Into<MiniStr>,
+  {
+    self.extend_envs([(key, value)])
+  }
+
+  /// Appends multiple environment variables, keeping the previously
+  /// configured ones.
+  pub fn extend_envs<I, K, V>(mut self, iter: I) -> Self
+  where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<MiniStr>,
+    V: Into<MiniStr>,
+  {
+    self.envs = extend_env_vars(self.envs.take(), iter);
+    self
   }
 
   /// Writes `stdin_data` to the child's stdin (if present) and return the
@@ -249,13 +566,30 @@ impl<'a> CommandSpawner<'a> {
   /// This returns the raw `std::process::Output` (bytes for stdout/stderr).
   /// Higher-level helpers (`capture_stdout`, `capture_stderr`,
   /// `capture_stdout_and_stderr`) decode those bytes into `DecodedText`.
+  ///
+  /// If `timeout` is set, see [CommandSpawner::wait_with_output_timeout()].
+  ///
+  /// If `max_capture_bytes` is set, each captured stream is read
+  /// incrementally and capped; see the field docs for the overflow behavior.
+  ///
+  /// `stdin_data` is written on a separate thread while the output is
+  /// drained, so large payloads do not deadlock.
   #[inline]
   pub fn capture_raw_output(
     self,
     cap_out: bool,
     cap_err: bool,
-  ) -> io::Result<std::process::Output> {
-    match (cap_out, cap_err) {
+  ) -> io::Result<Output> {
+    let Self {
+      timeout,
+      max_capture_bytes: limit,
+      truncate_capture,
+      ..
+    } = self;
+
+    // `stdin_data` is written on a separate thread below, not by `spawn()`.
+    let stdin_data = self.stdin_data;
+    let mut child = match (cap_out, cap_err) {
       (true, true) => self
         .with_stdout(StdioMode::Piped)
         .with_stderr(StdioMode::Piped),
@@ -263,8 +597,133 @@ impl<'a> CommandSpawner<'a> {
       (false, true) => self.with_stderr(StdioMode::Piped),
       _ => self,
     }
-    .spawn()?
-    .wait_with_output()
+    .pipe(|x| match stdin_data {
+      Some(_) => x
+        .with_stdin(StdioMode::Piped)
+        .with_stdin_data(None),
+      _ => x,
+    })
+    .spawn()?;
+
+    let mut output = thread::scope(|scope| {
+      let writer = match stdin_data {
+        Some(data) => child
+          .stdin
+          .take()
+          .ok_or_else(|| err_invalid_input("Failed to access child's stdin."))?
+          .pipe(|stdin| spawn_stdin_writer(scope, stdin, data))
+          .pipe(Some),
+        _ => None,
+      };
+
+      let output = match (timeout, limit) {
+        (None, None) => child.wait_with_output(),
+        _ => Self::wait_with_output_limited(child, timeout, limit),
+      };
+
+      if let Some(writer) = writer {
+        join_stdin_writer(writer)?
+      }
+      output
+    })?;
+
+    let Some(max) = limit else {
+      return Ok(output);
+    };
+
+    for buf in [&mut output.stdout, &mut output.stderr] {
+      match buf.len() > max {
+        false => {}
+        true if truncate_capture => buf.truncate(max),
+        _ => {
+          return format!("Captured output exceeded {max} bytes")
+            .pipe(io::Error::other)
+            .pipe(Err);
+        }
+      }
+    }
+    Ok(output)
+  }
+
+  /// Like [Child::wait_with_output], but gives up after `timeout`.
+  ///
+  /// The piped stdout/stderr are drained on separate threads, while the
+  /// current thread polls the child until it exits or the deadline passes.
+  ///
+  /// # Errors
+  ///
+  /// When the deadline passes, the child is killed and an error of kind
+  /// [TimedOut](io::ErrorKind::TimedOut) is returned. The same error is
+  /// returned if the child exited in time, but its stdout/stderr is still held
+  /// open (e.g., by a grandchild) at the deadline.
+  pub fn wait_with_output_timeout(
+    child: Child,
+    timeout: Duration,
+  ) -> io::Result<Output> {
+    Self::wait_with_output_limited(child, Some(timeout), None)
+  }
+
+  /// Drains the piped stdout/stderr on separate threads while waiting for the
+  /// child.
+  ///
+  /// - `timeout`: see [CommandSpawner::wait_with_output_timeout()].
+  /// - `limit`: each reader stops after `limit + 1` bytes and closes its pipe,
+  ///   so the caller can detect an overflow via `len() > limit`.
+  fn wait_with_output_limited(
+    mut child: Child,
+    timeout: Option<Duration>,
+    limit: Option<usize>,
+  ) -> io::Result<Output> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    // Same as `wait_with_output`: close stdin so the child doesn't wait on it.
+    drop(child.stdin.take());
+
+    let stdout = child
+      .stdout
+      .take()
+      .map(|x| spawn_reader(x, limit));
+    let stderr = child
+      .stderr
+      .take()
+      .map(|x| spawn_reader(x, limit));
+
+    let Some(timeout) = timeout else {
+      return Ok(Output {
+        status: child.wait()?,
+        stdout: join_reader(stdout, None)?,
+        stderr: join_reader(stderr, None)?,
+      });
+    };
+    let deadline = Instant::now() + timeout;
+
+    let status = loop {
+      if let Some(status) = child.try_wait()? {
+        break status;
+      }
+
+      match deadline.checked_duration_since(Instant::now()) {
+        Some(remaining) if !remaining.is_zero() => remaining
+          .min(POLL_INTERVAL)
+          .pipe(thread::sleep),
+        _ => {
+          child.kill()?;
+          child.wait()?;
+          return io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("Command timed out after {timeout:?}"),
+          )
+          .pipe(Err);
+        }
+      }
+    };
+
+    // The child has exited, but a grandchild may still hold the pipes.
+    Ok(Output {
+      status,
+      stdout: join_reader(stdout, Some(deadline))?,
+      stderr: join_reader(stderr, Some(deadline))?,
+    })
   }
 
   /// Captures stdout as decoded text.
@@ -287,22 +746,85 @@ impl<'a> CommandSpawner<'a> {
   /// # Ok::<(), std::io::Error>(())
   /// ```
   pub fn capture_stdout(self) -> io::Result<DecodedText> {
+    self
+      .capture_stdout_with_status()
+      .map(|(text, _)| text)
+  }
+
+  /// Captures stdout as decoded text, together with the exit status.
+  ///
+  /// A non-zero exit status is **not** treated as an error.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// let (stdout, status) = ["sh", "-c", "printf crashed; exit 2"]
+  ///   .pipe(CommandSpawner::from)
+  ///   .capture_stdout_with_status()?;
+  /// assert_eq!(stdout.data(), "crashed");
+  /// assert_eq!(status.code(), Some(2));
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn capture_stdout_with_status(self) -> io::Result<(DecodedText, ExitStatus)> {
     self
       .capture_raw_output(true, false)?
-      .stdout
-      .pipe(DecodedText::from_vec)
+      .pipe(|o| (DecodedText::from_vec(o.stdout), o.status))
       .pipe(Ok)
   }
 
+  /// Captures stdout via [Self::capture_stdout()] and checks that it contains
+  /// `needle`.
+  ///
+  /// Returns the captured text on success. If `needle` is missing, returns an
+  /// error of kind `Other` whose message shows the actual output.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// let v = ["printf", "%s", "hello world"]
+  ///   .pipe(CommandSpawner::from)
+  ///   .assert_stdout_contains("world")?;
+  /// assert_eq!(v.data(), "hello world");
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn assert_stdout_contains(self, needle: &str) -> io::Result<DecodedText> {
+    let text = self.capture_stdout()?;
+
+    match text.contains(needle) {
+      true => Ok(text),
+      _ => io::Error::other(format!(
+        "stdout does not contain {needle:?}, actual output:\n{}",
+        text.data()
+      ))
+      .pipe(Err),
+    }
+  }
+
   /// Captures stderr as decoded text.
   ///
   /// This forces stderr to `Piped`, spawns the child, waits for completion,
   /// and decodes `output.stderr` into `DecodedText`.
   pub fn capture_stderr(self) -> io::Result<DecodedText> {
+    self
+      .capture_stderr_with_status()
+      .map(|(text, _)| text)
+  }
+
+  /// Captures stderr as decoded text, together with the exit status.
+  ///
+  /// A non-zero exit status is **not** treated as an error.
+  pub fn capture_stderr_with_status(self) -> io::Result<(DecodedText, ExitStatus)> {
     self
       .capture_raw_output(false, true)?
-      .stderr
-      .pipe(DecodedText::from_vec)
+      .pipe(|o| (DecodedText::from_vec(o.stderr), o.status))
       .pipe(Ok)
   }
 
@@ -334,6 +856,249 @@ impl<'a> CommandSpawner<'a> {
       .map(DecodedText::from_vec)
       .pipe(Ok)
   }
+
+  /// Captures stdout while writing `stdin_data` on a separate thread.
+  ///
+  /// [spawn](Self::spawn) writes `stdin_data` synchronously before returning,
+  /// which can deadlock for large payloads: the child fills its stdout pipe
+  /// and blocks, while the parent is still blocked writing stdin.
+  ///
+  /// Same as [capture_stdout](Self::capture_stdout), which already writes
+  /// stdin from another thread (and applies `timeout` and
+  /// `max_capture_bytes`).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// let v = "cat"
+  ///   .pipe(CommandSpawner::from)
+  ///   .with_stdin_data(Some(b"hello"))
+  ///   .capture_stdout_with_stdin()?;
+  /// assert_eq!(v.data(), "hello");
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn capture_stdout_with_stdin(self) -> io::Result<DecodedText> {
+    self.capture_stdout()
+  }
+
+  /// Streams stdout line-by-line, calling `f` for each line as it arrives.
+  ///
+  /// This forces stdout to `Piped`, spawns the child, and reads its stdout
+  /// until EOF. Each line is passed to `f` without the trailing `\n` (or
+  /// `\r\n`); invalid UTF-8 is replaced with `U+FFFD`. Finally, it waits
+  /// for the child and returns its exit status (a non-zero exit status is
+  /// **not** treated as an error).
+  ///
+  /// If reading stdout fails (or `f` panics), the child is killed and reaped
+  /// (see [SpawnGuard]). `timeout` is ignored here.
+  ///
+  /// ## Deadlock caveat
+  ///
+  /// stderr is not read by this method. If stderr is set to `Piped`, the child
+  /// may block once the stderr pipe buffer fills up, and the stdout loop will
+  /// never reach EOF. Leave stderr as `Inherit`/`Null`, or redirect it to a
+  /// file.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// let mut lines = Vec::new();
+  /// let status = ["printf", "a\nb\n"]
+  ///   .pipe(CommandSpawner::from)
+  ///   .for_each_stdout_line(|line| lines.push(line.to_owned()))?;
+  ///
+  /// assert!(status.success());
+  /// assert_eq!(lines, ["a", "b"]);
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn for_each_stdout_line<F: FnMut(&str)>(
+    self,
+    mut f: F,
+  ) -> io::Result<ExitStatus> {
+    // Killed and reaped on drop if reading fails (or `f` panics).
+    let mut child = self
+      .with_stdout(StdioMode::Piped)
+      .spawn_guarded()?;
+
+    if let Some(stdout) = child.stdout.take() {
+      for line in io::BufReader::new(stdout).split(b'\n') {
+        let line = line?;
+        let line = line
+          .strip_suffix(b"\r")
+          .unwrap_or(&line);
+        f(&String::from_utf8_lossy(line));
+      }
+    }
+
+    child.wait()
+  }
+}
+
+#[cfg(feature = "tokio")]
+impl CommandSpawner<'_> {
+  /// Async version of [CommandSpawner::spawn()], via `tokio::process`.
+  ///
+  /// The stdio modes, envs, and working directory are applied the same way
+  /// (see [CommandSpawner::into_command()]). `stdin_data` is written
+  /// asynchronously before returning.
+  ///
+  /// Note: `timeout` and `max_capture_bytes` are not applied here; use
+  /// e.g. `tokio::time::timeout` on the returned child instead.
+  pub async fn spawn_async(self) -> io::Result<tokio::process::Child> {
+    use tokio::io::AsyncWriteExt;
+
+    let (command, stdin_data) = self.into_command()?;
+    let program = command
+      .get_program()
+      .to_os_string();
+
+    let mut child = tokio::process::Command::from(command)
+      .spawn()
+      .map_err(err_spawn_failed(&program))?;
+
+    if let Some(data) = stdin_data {
+      child
+        .stdin
+        .as_mut()
+        .ok_or_else(|| err_invalid_input("Failed to access child's stdin."))?
+        .write_all(data)
+        .await?
+    }
+    Ok(child)
+  }
+
+  /// Async version of [CommandSpawner::capture_stdout()].
+  ///
+  /// This forces stdout to `Piped`, awaits the child, and decodes its stdout
+  /// into `DecodedText`. A non-zero exit status is **not** treated as an
+  /// error.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+  ///
+  /// # tokio::runtime::Builder::new_current_thread()
+  /// #   .enable_all()
+  /// #   .build()?
+  /// #   .block_on(async {
+  /// let v = ["printf", "%s", "hello"]
+  ///   .pipe(CommandSpawner::from)
+  ///   .capture_stdout_async()
+  ///   .await?;
+  /// assert_eq!(v.data(), "hello");
+  /// # Ok::<(), std::io::Error>(())
+  /// # })?;
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub async fn capture_stdout_async(self) -> io::Result<DecodedText> {
+    self
+      .with_stdout(StdioMode::Piped)
+      .spawn_async()
+      .await?
+      .wait_with_output()
+      .await?
+      .stdout
+      .pipe(DecodedText::from_vec)
+      .pipe(Ok)
+  }
+}
+
+/// Receives the result of a [spawn_reader()] thread.
+///
+/// A channel instead of a `JoinHandle`, so that waiting can be bounded by a
+/// deadline (see [join_reader()]).
+type ReaderHandle = mpsc::Receiver<io::Result<Vec<u8>>>;
+
+/// Reads the pipe to the end on a separate thread.
+///
+/// With `limit`, it reads at most `limit + 1` bytes, then drops (closes) the
+/// pipe.
+fn spawn_reader<R: Read + Send + 'static>(
+  mut pipe: R,
+  limit: Option<usize>,
+) -> ReaderHandle {
+  let (tx, rx) = mpsc::channel();
+  thread::spawn(move || {
+    let mut buf = Vec::new();
+    let result = match limit {
+      Some(n) => pipe
+        .by_ref()
+        .take((n as u64).saturating_add(1))
+        .read_to_end(&mut buf),
+      _ => pipe.read_to_end(&mut buf),
+    }
+    .map(|_| buf);
+    // Fails only if the caller has already given up (deadline passed).
+    let _ = tx.send(result);
+  });
+  rx
+}
+
+/// Writes `data` into the child's stdin on a scoped thread, so the caller can
+/// drain stdout/stderr at the same time (no pipe deadlock).
+fn spawn_stdin_writer<'scope>(
+  scope: &'scope thread::Scope<'scope, '_>,
+  mut stdin: ChildStdin,
+  data: &'scope [u8],
+) -> thread::ScopedJoinHandle<'scope, io::Result<()>> {
+  scope.spawn(move || {
+    // Dropping `stdin` at the end closes the pipe, so the child sees EOF.
+    match stdin.write_all(data) {
+      // The child may exit without reading all of its input.
+      Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+      x => x,
+    }
+  })
+}
+
+fn join_stdin_writer(
+  writer: thread::ScopedJoinHandle<'_, io::Result<()>>,
+) -> io::Result<()> {
+  writer
+    .join()
+    .map_err(|_| io::Error::other("stdin writer thread panicked"))?
+}
+
+/// Waits for a reader's result, giving up at `deadline` if set.
+///
+/// On timeout, the reader thread is left behind; it exits once the pipe is
+/// closed.
+fn join_reader(
+  handle: Option<ReaderHandle>,
+  deadline: Option<Instant>,
+) -> io::Result<Vec<u8>> {
+  use mpsc::RecvTimeoutError::*;
+
+  let Some(rx) = handle else {
+    return Ok(Vec::new());
+  };
+  let panicked = || io::Error::other("pipe reader thread panicked");
+
+  match deadline {
+    Some(d) => rx
+      .recv_timeout(d.saturating_duration_since(Instant::now()))
+      .map_err(|e| match e {
+        Timeout => io::Error::new(
+          io::ErrorKind::TimedOut,
+          "Command output was still open at the deadline",
+        ),
+        Disconnected => panicked(),
+      })?,
+    _ => rx
+      .recv()
+      .map_err(|_| panicked())?,
+  }
 }
 
 impl<'a, T> From<T> for CommandSpawner<'a>
@@ -341,20 +1106,19 @@ where
   T: Into<Runner<'a>>,
 {
   fn from(value: T) -> Self {
-    let Runner {
-      command,
-      remove_comments,
-      stdin_data,
-      ..
-    } = value.into();
+    let mut runner = value.into();
+    let stdin_data = runner.stdin_data;
+    let cwd = runner.cwd.take();
+    let envs = runner.envs.take();
+    let env_clear = runner.env_clear;
 
-    command
-      .into_tinyvec(remove_comments)
-      .into_iter()
-      .map(super::cow_str_into_cow_osstr)
-      .collect::<CowOsStrVec<_>>()
+    runner
+      .into_os_tinyvec()
       .pipe(|x| CommandSpawner::default().with_argv(x))
       .with_stdin_data(stdin_data)
+      .with_working_dir(cwd)
+      .with_envs(envs)
+      .with_env_clear(env_clear)
   }
 }
 
@@ -386,4 +1150,307 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn capture_with_status() -> io::Result<()> {
+    let script = "printf out; printf err >&2; exit 1";
+
+    let (stdout, status) = ["sh", "-c", script]
+      .pipe(CommandSpawner::from)
+      .with_stderr(StdioMode::Null)
+      .capture_stdout_with_status()?;
+    assert_eq!(stdout.data(), "out");
+    assert!(!status.success());
+
+    let (stderr, status) = ["sh", "-c", script]
+      .pipe(CommandSpawner::from)
+      .with_stdout(StdioMode::Null)
+      .capture_stderr_with_status()?;
+    assert_eq!(stderr.data(), "err");
+    assert_eq!(status.code(), Some(1));
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn redirect_stdout_to_file() -> io::Result<()> {
+    let path = std::env::temp_dir().join("testutils-stdio-mode-file.txt");
+
+    let run = |mode| {
+      ["printf", "hi"]
+        .pipe(CommandSpawner::from)
+        .with_stdout(mode)
+        .spawn()?
+        .wait()
+    };
+
+    run(StdioMode::File(path.clone()))?;
+    run(StdioMode::File(path.clone()))?;
+    assert_eq!(fs::read_to_string(&path)?, "hi");
+
+    run(StdioMode::Append(path.clone()))?;
+    assert_eq!(fs::read_to_string(&path)?, "hihi");
+
+    fs::remove_file(path)
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn redirect_stdout_and_stderr_to_same_file() -> io::Result<()> {
+    let path = std::env::temp_dir().join("testutils-stdio-mode-out-err.txt");
+
+    ["sh", "-c", "echo out; echo err >&2"]
+      .pipe(CommandSpawner::from)
+      .with_out_err(StdioMode::File(path.clone()))
+      .spawn()?
+      .wait()?;
+    assert_eq!(fs::read_to_string(&path)?, "out\nerr\n");
+
+    fs::remove_file(path)
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn stream_stdout_lines() -> io::Result<()> {
+    let mut count = 0;
+    let mut last = String::new();
+
+    let status = ["sh", "-c", "for i in 1 2 3; do echo line$i; done"]
+      .pipe(CommandSpawner::from)
+      .for_each_stdout_line(|line| {
+        count += 1;
+        last = line.into();
+      })?;
+
+    assert!(status.success());
+    assert_eq!(count, 3);
+    assert_eq!(last, "line3");
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn stream_stdout_lines_reaps_child_on_unwind() {
+    let mut pid = String::new();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      ["sh", "-c", "echo $$; exec sleep 30"]
+        .pipe(CommandSpawner::from)
+        .for_each_stdout_line(|line| {
+          pid = line.into();
+          panic!("stop reading");
+        })
+    }));
+    assert!(result.is_err());
+
+    let proc_dir = std::path::Path::new("/proc").join(&pid);
+    assert!(!proc_dir.exists(), "child {pid} is still around");
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn capture_stdout_with_large_stdin() -> io::Result<()> {
+    let data = vec![b'x'; 1024 * 1024];
+
+    let v = "cat"
+      .pipe(CommandSpawner::from)
+      .with_stdin_data(Some(&data))
+      .capture_stdout_with_stdin()?;
+    assert_eq!(v.data().len(), data.len());
+
+    Ok(())
+  }
+
+  #[test]
+  fn set_stdio_at_once() {
+    let spawner = CommandSpawner::default().with_out_err(StdioMode::Piped);
+    assert_eq!(spawner.get_stdin(), &StdioMode::Inherit);
+    assert_eq!(spawner.get_stdout(), &StdioMode::Piped);
+    assert_eq!(spawner.get_stderr(), &StdioMode::Piped);
+
+    let spawner = spawner.with_stdio(StdioMode::Null);
+    assert_eq!(
+      [
+        spawner.get_stdin(),
+        spawner.get_stdout(),
+        spawner.get_stderr()
+      ],
+      [&StdioMode::Null; 3]
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn assert_stdout_contains_needle() -> io::Result<()> {
+    let spawner = ["printf", "%s", "hello world"].pipe(CommandSpawner::from);
+
+    let v = spawner
+      .clone()
+      .assert_stdout_contains("lo wo")?;
+    assert_eq!(v.data(), "hello world");
+
+    let err = spawner
+      .assert_stdout_contains("bye")
+      .expect_err("needle is missing");
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert!(
+      err
+        .to_string()
+        .contains("hello world"),
+      "{err}"
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn capture_with_timeout() -> io::Result<()> {
+    let err = "sleep 5"
+      .pipe(CommandSpawner::from)
+      .with_timeout(Some(Duration::from_millis(100)))
+      .capture_stdout()
+      .expect_err("sleep 5 should time out");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    let v = ["printf", "%s", "in time"]
+      .pipe(CommandSpawner::from)
+      .with_timeout(Some(Duration::from_secs(10)))
+      .capture_stdout()?;
+    assert_eq!(v.data(), "in time");
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn capture_timeout_covers_grandchild_holding_pipe() {
+    let start = Instant::now();
+    let err = ["sh", "-c", "sleep 5 & echo hi"]
+      .pipe(CommandSpawner::from)
+      .with_timeout(Some(Duration::from_millis(200)))
+      .capture_stdout()
+      .expect_err("the background sleep keeps stdout open");
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(3));
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn capture_with_max_bytes() -> io::Result<()> {
+    let spawner = "yes"
+      .pipe(CommandSpawner::from)
+      .with_max_capture_bytes(Some(16))
+      // Safety net in case the pipe isn't closed.
+      .with_timeout(Some(Duration::from_secs(10)));
+
+    let err = spawner
+      .clone()
+      .capture_stdout()
+      .expect_err("`yes` never stops printing");
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+
+    let stdout = spawner
+      .with_truncate_capture(true)
+      .capture_stdout()?;
+    assert_eq!(stdout.data(), "y\n".repeat(8));
+
+    // Within the cap, the output is unchanged.
+    let stdout = ["printf", "%s", "hello"]
+      .pipe(CommandSpawner::from)
+      .with_max_capture_bytes(Some(5))
+      .capture_stdout()?;
+    assert_eq!(stdout.data(), "hello");
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn spawn_with_no_window() -> io::Result<()> {
+    let stdout = ["cmd", "/C", "echo hello"]
+      .pipe(CommandSpawner::from)
+      .with_no_window(true)
+      .capture_stdout()?;
+    assert_eq!(stdout.trim_ascii_end(), "hello");
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn spawn_with_env() -> io::Result<()> {
+    let v = ["sh", "-c", r#"printf "%s-%s" "$FOO" "$BAR""#]
+      .pipe(CommandSpawner::from)
+      .with_env("FOO", "foo")
+      .extend_envs([("BAR", "bar")])
+      .capture_stdout()?;
+    assert_eq!(v.data(), "foo-bar");
+
+    Ok(())
+  }
+
+  #[test]
+  fn filter_vars_with_allowlist_prefix() {
+    let vars = [
+      ("CARGO_PKG_NAME", "testutils"),
+      ("TESTUTILS_NOT_ALLOWED", "1"),
+      ("PATH", "/usr/bin"),
+      ("CARGO_HOME", "/cargo"),
+    ]
+    .map(|(k, v)| (OsString::from(k), OsString::from(v)));
+
+    let kept = filter_vars_by_prefix(vars, "CARGO_")
+      .map(|(k, _)| k)
+      .collect::<Vec<_>>();
+    assert_eq!(kept, ["CARGO_PKG_NAME", "CARGO_HOME"]);
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn spawn_with_env_allowlist_prefix() -> io::Result<()> {
+    let v = "/usr/bin/env"
+      .pipe(CommandSpawner::from)
+      .with_env_allowlist_prefix(Some("CARGO_".into()))
+      .with_env("TESTUTILS_EXPLICIT", "1")
+      .capture_stdout()?;
+
+    // Set by cargo for this test binary.
+    assert!(v.contains("CARGO_PKG_NAME=testutils"));
+    assert!(!v.contains("\nPATH="));
+    // `envs` are applied after the filter.
+    assert!(v.contains("TESTUTILS_EXPLICIT=1"));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn spawn_with_env_clear() -> io::Result<()> {
+    let v = "/usr/bin/env"
+      .pipe(CommandSpawner::from)
+      .with_env_clear(true)
+      .with_env("ONLY_THIS", "1")
+      .capture_stdout()?;
+    assert_eq!(v.data(), "ONLY_THIS=1\n");
+
+    Ok(())
+  }
+
+  #[cfg(all(feature = "tokio", unix))]
+  #[tokio::test]
+  async fn capture_echo_async() -> io::Result<()> {
+    let v = ["echo", "hello"]
+      .pipe(CommandSpawner::from)
+      .capture_stdout_async()
+      .await?;
+    assert_eq!(v.trim_ascii_end(), "hello");
+
+    let v = "cat"
+      .pipe(CommandSpawner::from)
+      .with_stdin_data(Some(b"from stdin"))
+      .capture_stdout_async()
+      .await?;
+    assert_eq!(v.data(), "from stdin");
+    Ok(())
+  }
 }
@@ -1,8 +1,12 @@
 use alloc::borrow::Cow;
+use std::ffi::OsString;
 
 use tap::Pipe;
 
-use crate::os_cmd::{MiniStr, RunnableCommand, presets::CowStrVec};
+use crate::os_cmd::{
+  CowOsStrVec, MiniStr, RunnableCommand, collect::quote_arg, cow_str_into_cow_osstr,
+  presets::CowStrVec,
+};
 pub(crate) type TinyCmds<'a> = CowStrVec<'a, 9>;
 
 impl<'a> RunnableCommand<'a> for CommandRepr<'a> {}
@@ -14,11 +18,15 @@ impl<'a> RunnableCommand<'a> for CommandRepr<'a> {}
 ///   "fmt"].into_boxed_slice()`)
 /// - OwnedSlice: `Box<[MiniStr]>` (e.g., `["cargo", "+nightly",
 ///   "fmt"].pipe(collect_boxed_ministr_slice)`)
+/// - OsSlice: `Box<[OsString]>`, for programs/arguments that may not be valid
+///   UTF-8 (e.g., unusual install paths). They are passed to
+///   `std::process::Command` as-is, without a UTF-8 round-trip.
 #[derive(Debug, Clone)]
 pub enum CommandRepr<'a> {
   Raw(&'a str),
   Slice(Box<[&'a str]>),
   OwnedSlice(Box<[MiniStr]>),
+  OsSlice(Box<[OsString]>),
 }
 
 impl Default for CommandRepr<'_> {
@@ -40,6 +48,18 @@ impl From<Vec<MiniStr>> for CommandRepr<'_> {
   }
 }
 
+impl From<Box<[OsString]>> for CommandRepr<'_> {
+  fn from(value: Box<[OsString]>) -> Self {
+    Self::OsSlice(value)
+  }
+}
+
+impl From<Vec<OsString>> for CommandRepr<'_> {
+  fn from(value: Vec<OsString>) -> Self {
+    Self::OsSlice(value.into())
+  }
+}
+
 impl<'a> From<Box<[&'a str]>> for CommandRepr<'a> {
   fn from(value: Box<[&'a str]>) -> Self {
     Self::Slice(value)
@@ -69,6 +89,9 @@ impl<'a> CommandRepr<'a> {
   /// - Slice(Box<[&str]>) => `TinyVec<[Cow<&str>]>`
   /// - OwnedSlice(Box<[compact_str::CompactString]>) =>
   ///   `TinyVec<[Cow<String>]>`
+  /// - OsSlice(Box<[OsString]>) => `TinyVec<[Cow<String>]>`, converted lossily
+  ///   (invalid UTF-8 becomes `U+FFFD`). Use
+  ///   [into_os_tinyvec](Self::into_os_tinyvec) to keep the original bytes.
   pub fn into_tinyvec(self, remove_comments: bool) -> TinyCmds<'a> {
     match self {
       Self::Raw(raw) => collect_raw(raw, remove_comments),
@@ -80,10 +103,177 @@ impl<'a> CommandRepr<'a> {
         .into_iter()
         .map(Cow::from)
         .collect(),
+      Self::OsSlice(items) => items
+        .into_iter()
+        .map(|x| x.to_string_lossy().into_owned())
+        .map(Cow::from)
+        .collect(),
+    }
+  }
+
+  /// Like [into_tinyvec](Self::into_tinyvec), but collects into OS strings.
+  ///
+  /// OsSlice items are moved as-is; the other variants are converted from
+  /// `str`.
+  pub fn into_os_tinyvec(self, remove_comments: bool) -> CowOsStrVec<'a, 9> {
+    match self {
+      Self::OsSlice(items) => items
+        .into_iter()
+        .map(Cow::from)
+        .collect(),
+      x => x
+        .into_tinyvec(remove_comments)
+        .into_iter()
+        .map(cow_str_into_cow_osstr)
+        .collect(),
+    }
+  }
+
+  /// Materializes the command into owned args, without running it.
+  ///
+  /// This is the same collection step as [Runner](crate::os_cmd::Runner)'s
+  /// Phase 1, so the argv can be inspected or modified before execution:
+  ///
+  /// - Raw: shlex-split (quotes are honored); `//` comment lines are removed
+  ///   when `remove_comments` is true.
+  /// - Slice/OwnedSlice: taken as-is.
+  /// - OsSlice: converted lossily, see [into_tinyvec](Self::into_tinyvec).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::{CommandRepr, Runner};
+  ///
+  /// let mut args = CommandRepr::Raw(r#"printf "%s" 'hello world'"#).into_args(false);
+  /// assert_eq!(args, ["printf", "%s", "hello world"]);
+  ///
+  /// args.push("!".into());
+  /// let _runner = Runner::from(args);
+  /// ```
+  pub fn into_args(self, remove_comments: bool) -> Vec<MiniStr> {
+    match self {
+      Self::OwnedSlice(items) => items.into_vec(),
+      x => x
+        .into_tinyvec(remove_comments)
+        .into_iter()
+        .map(MiniStr::from)
+        .collect(),
     }
   }
 }
 
+impl core::fmt::Display for CommandRepr<'_> {
+  /// Formats the command as a shell-like one-liner.
+  ///
+  /// - Raw: printed verbatim.
+  /// - Others: args are joined with spaces; an arg containing whitespace or
+  ///   shell metacharacters is quoted, same as
+  ///   [join_quoted](crate::os_cmd::join_quoted).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::CommandRepr;
+  ///
+  /// let cmd = CommandRepr::from(["echo", "hello world"]);
+  /// assert_eq!(cmd.to_string(), "echo 'hello world'");
+  /// ```
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Raw(raw) => f.write_str(raw),
+      Self::Slice(items) => fmt_quoted_args(f, items.iter().copied()),
+      Self::OwnedSlice(items) => {
+        fmt_quoted_args(f, items.iter().map(|x| x.as_str()))
+      }
+      Self::OsSlice(items) => fmt_quoted_args(
+        f,
+        items
+          .iter()
+          .map(|x| x.to_string_lossy())
+          .collect::<Vec<_>>()
+          .iter()
+          .map(|x| x.as_ref()),
+      ),
+    }
+  }
+}
+
+impl core::hash::Hash for CommandRepr<'_> {
+  /// Hashes the materialized argv, not the variant.
+  ///
+  /// Normalization: `Raw` is shlex-split first (with `//` comment lines
+  /// removed, same as [Runner](crate::os_cmd::Runner)'s default), and every
+  /// arg is hashed as an OS string. So `Raw("cargo fmt")`,
+  /// `Slice(["cargo", "fmt"])` and the equivalent `OwnedSlice`/`OsSlice` hash
+  /// identically.
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self
+      .clone()
+      .into_os_tinyvec(true)
+      .as_slice()
+      .hash(state)
+  }
+}
+
+impl CommandRepr<'_> {
+  /// Hashes the command (see the [Hash](core::hash::Hash) impl) with
+  /// `DefaultHasher`, e.g., as a build cache key.
+  ///
+  /// The value is stable across runs, but may change between Rust releases.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::CommandRepr;
+  ///
+  /// assert_eq!(
+  ///   CommandRepr::Raw("cargo +nightly fmt").command_hash(),
+  ///   CommandRepr::from(["cargo", "+nightly", "fmt"]).command_hash(),
+  /// );
+  /// ```
+  pub fn command_hash(&self) -> u64 {
+    use core::hash::{Hash, Hasher};
+
+    let mut hasher = std::hash::DefaultHasher::new();
+    self.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Compares the materialized argv of both commands element-wise, with the
+  /// same normalization as the [Hash](core::hash::Hash) impl, so
+  /// `a.args_eq(&b)` implies equal [command_hash](Self::command_hash)es.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::CommandRepr;
+  ///
+  /// let raw = CommandRepr::Raw("cargo  +nightly\n  fmt");
+  /// assert!(raw.args_eq(&CommandRepr::from(["cargo", "+nightly", "fmt"])));
+  /// assert!(!raw.args_eq(&CommandRepr::from(["cargo", "fmt"])));
+  /// ```
+  pub fn args_eq(&self, other: &CommandRepr) -> bool {
+    let [a, b] = [self, other].map(|x| x.clone().into_os_tinyvec(true));
+    a == b
+  }
+}
+
+/// Writes space-separated, shell-quoted args. See also: [join_quoted]
+///
+/// [join_quoted]: crate::os_cmd::join_quoted
+fn fmt_quoted_args<S: AsRef<str>>(
+  f: &mut core::fmt::Formatter<'_>,
+  args: impl Iterator<Item = S>,
+) -> core::fmt::Result {
+  for (i, arg) in args.enumerate() {
+    if i != 0 {
+      f.write_str(" ")?
+    }
+    f.write_str(&quote_arg(arg.as_ref()))?
+  }
+  Ok(())
+}
+
 /// Parses raw command string into executable components
 ///
 /// Why TinyVec:
@@ -114,7 +304,7 @@ pub fn collect_raw(raw: &str, remove_comments: bool) -> TinyCmds<'_> {
   raw
     .trim_ascii() // Trim ASCII whitespace efficiently (rust 1.80+)
     .pipe(|s| match remove_comments {
-      true => remove_comments_and_collect(s),
+      true => strip_comment_lines(s),
       _ => s.into(), // Convert to Cow without cloning
     })
     .pipe_deref(shlex::Shlex::new) // Safe command line splitting
@@ -122,7 +312,105 @@ pub fn collect_raw(raw: &str, remove_comments: bool) -> TinyCmds<'_> {
     .collect()
 }
 
-/// Preprocesses command string by removing comment lines
+/// Like [collect_raw], but first joins lines ending in a shell-style `\`
+/// continuation into a single logical line.
+///
+/// When `remove_comments` is true, `//` comment lines are filtered first,
+/// then continuations are joined. So a comment line between two continued
+/// lines doesn't break the continuation.
+///
+/// Only a `\` outside quotes continues the line. Within quotes, it is left to
+/// the splitting, like in [collect_raw], e.g., `'a \` + line break + `b'` keeps
+/// both the `\` and the line break.
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::collect_raw_with_continuations;
+///
+/// let raw = r#"
+///   cargo +nightly \
+///   // toolchain-specific flags
+///     build --release
+/// "#;
+///
+/// let vec = collect_raw_with_continuations(raw, true);
+/// assert_eq!(vec.as_ref(), &["cargo", "+nightly", "build", "--release"]);
+/// ```
+pub fn collect_raw_with_continuations(
+  raw: &str,
+  remove_comments: bool,
+) -> TinyCmds<'_> {
+  let raw = raw.trim_ascii();
+  let raw = match remove_comments {
+    true => strip_comment_lines(raw),
+    _ => raw.into(),
+  };
+
+  join_continuations(&raw)
+    .pipe_deref(shlex::Shlex::new)
+    .map(Cow::from)
+    .collect()
+}
+
+/// Replaces each `\` + line break outside quotes with a space.
+///
+/// Quotes and escapes are tracked as in [strip_comment_lines], so a `\` before
+/// a line break within quotes is kept as is (e.g., literally within single
+/// quotes).
+fn join_continuations(s: &str) -> Cow<'_, str> {
+  if !s.contains('\\') {
+    return s.into();
+  }
+
+  let mut out = String::with_capacity(s.len());
+  // The currently open quote char, if any.
+  let mut quote = None;
+  let mut chars = s.chars();
+
+  while let Some(c) = chars.next() {
+    match (quote, c) {
+      (None | Some('"'), '\\') => {
+        let rest = chars.as_str();
+        match rest
+          .strip_prefix('\n')
+          .or_else(|| rest.strip_prefix("\r\n"))
+        {
+          Some(next_line) if quote.is_none() => {
+            chars = next_line.chars();
+            out.push(' ');
+          }
+          _ => {
+            out.push(c);
+            out.extend(chars.next());
+          }
+        }
+        continue;
+      }
+      (None, '\'' | '"') => quote = Some(c),
+      (Some(q), _) if q == c => quote = None,
+      _ => {}
+    }
+    out.push(c);
+  }
+  out.into()
+}
+
+/// Preprocesses command string by removing `//` comments
+///
+/// This is the same filtering a [Runner](super::Runner) applies to raw command
+/// strings (with `remove_comments` enabled), so it can be reused before
+/// handing a string to other tools.
+///
+/// A `//` starts a comment (until the end of the line) only when it is
+/// outside quotes and at a token boundary, i.e., at the start of the input or
+/// right after whitespace. So:
+///
+/// - `// comment` and `cargo build // comment` are stripped.
+/// - `http://example.com`, `"// quoted"` and `'a // b'` are kept.
+///
+/// Escapes follow the shell rules: `\` escapes the next char outside quotes
+/// and within double quotes, but not within single quotes.
 ///
 /// Why Cow:
 ///
@@ -133,26 +421,244 @@ pub fn collect_raw(raw: &str, remove_comments: bool) -> TinyCmds<'_> {
 ///   - When it is false, it directly returns the original string (`&str`).
 ///   - To ensure both conditions return the same type, this function wraps the
 ///     returned string in `Cow`.
-pub fn remove_comments_and_collect(s: &str) -> Cow<'_, str> {
+///
+/// ## Example
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use testutils::os_cmd::strip_comment_lines;
+///
+/// let raw = "// fetch\ncurl http://example.com";
+/// assert_eq!(strip_comment_lines(raw), "\ncurl http://example.com");
+///
+/// // Without comments, the input is returned as is.
+/// let raw = "curl http://example.com";
+/// assert!(matches!(strip_comment_lines(raw), Cow::Borrowed(x) if x == raw));
+/// ```
+pub fn strip_comment_lines(s: &str) -> Cow<'_, str> {
   if !s.contains("//") {
     return s.into();
   }
 
-  s.lines()
-    // Since shell commands can contain `\"\n{space} \"`,
-    // don't use `.map(|x| x.trim())` here.
-    .filter(|x| {
-      !x.trim_ascii_start()
-        .starts_with("//")
-    })
-    .collect::<String>()
-    .pipe(Cow::from)
+  let mut out = String::with_capacity(s.len());
+  let mut stripped = false;
+  // The currently open quote char, if any.
+  let mut quote = None;
+  let mut at_boundary = true;
+  let mut chars = s.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match (quote, c) {
+      (None | Some('"'), '\\') => {
+        let next = chars.next();
+        out.push(c);
+        out.extend(next);
+        // An escaped line break (continuation) starts a new line, just like a
+        // bare one.
+        at_boundary = quote.is_none() && next == Some('\n');
+        continue;
+      }
+      (None, '\'' | '"') => quote = Some(c),
+      (Some(q), _) if q == c => quote = None,
+      (None, '/') if at_boundary && chars.peek() == Some(&'/') => {
+        // Skip to the end of the line, but keep the line break, since shell
+        // commands can contain `"\n{space} "`.
+        while chars
+          .next_if(|&x| x != '\n')
+          .is_some()
+        {}
+        stripped = true;
+        continue;
+      }
+      _ => {}
+    }
+    at_boundary = quote.is_none() && c.is_ascii_whitespace();
+    out.push(c);
+  }
+
+  match stripped {
+    true => out.into(),
+    _ => s.into(),
+  }
+}
+
+/// Same as [strip_comment_lines].
+///
+/// ## Example
+///
+/// ```
+/// use testutils::os_cmd::remove_comments_and_collect;
+///
+/// let raw = "curl // fetch\n  http://example.com";
+/// assert_eq!(
+///   remove_comments_and_collect(raw),
+///   "curl \n  http://example.com"
+/// );
+/// ```
+pub fn remove_comments_and_collect(s: &str) -> Cow<'_, str> {
+  strip_comment_lines(s)
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn args_eq_across_variants() {
+    use crate::os_cmd::collect_boxed_ministr_slice;
+
+    let raw = CommandRepr::Raw("cargo +nightly fmt");
+    let slice = CommandRepr::from(["cargo", "+nightly", "fmt"]);
+    let owned = ["cargo", "+nightly", "fmt"]
+      .pipe(collect_boxed_ministr_slice)
+      .pipe(CommandRepr::from);
+
+    assert!(raw.args_eq(&slice));
+    assert!(slice.args_eq(&owned));
+    assert!(owned.args_eq(&raw));
+    assert!(!raw.args_eq(&CommandRepr::from(["cargo", "fmt"])));
+  }
+
+  #[test]
+  fn args_eq_ignores_raw_whitespace() {
+    let a = CommandRepr::Raw("printf '%s' 'a b'");
+    let b = CommandRepr::Raw(
+      "  printf\t'%s'
+         'a b'  ",
+    );
+    assert!(a.args_eq(&b));
+
+    // Whitespace inside quotes is part of the token.
+    assert!(!a.args_eq(&CommandRepr::Raw("printf '%s' 'a  b'")));
+  }
+
+  #[test]
+  fn raw_and_slice_hash_equally() {
+    let raw = CommandRepr::Raw("cargo fmt");
+    assert_eq!(
+      raw.command_hash(),
+      CommandRepr::from(["cargo", "fmt"]).command_hash()
+    );
+    assert_eq!(
+      raw.command_hash(),
+      CommandRepr::from(vec![OsString::from("cargo"), OsString::from("fmt")])
+        .command_hash()
+    );
+    assert_ne!(
+      raw.command_hash(),
+      CommandRepr::from(["cargo", "fmt", "--check"]).command_hash()
+    );
+    // Arg boundaries matter: "cargo fmt" as one arg != two args.
+    assert_ne!(
+      raw.command_hash(),
+      CommandRepr::from(["cargo fmt"]).command_hash()
+    );
+  }
+
+  #[test]
+  fn join_line_continuations() {
+    let raw = "printf '%s-%s\\n' \\\n  hello \\\r\n  world";
+    let vec = collect_raw_with_continuations(raw, false);
+    assert_eq!(vec.as_ref(), &["printf", "%s-%s\\n", "hello", "world"]);
+
+    // An escaped trailing backslash is not a continuation.
+    let vec = collect_raw_with_continuations("echo a\\\\\necho", false);
+    assert_eq!(vec.as_ref(), &["echo", "a\\", "echo"]);
+
+    // Within single quotes, `\` + line break is kept as is (as in
+    // `collect_raw`).
+    let raw = "echo 'a \\\nb'";
+    let vec = collect_raw_with_continuations(raw, false);
+    assert_eq!(vec.as_ref(), &["echo", "a \\\nb"]);
+    assert_eq!(vec, collect_raw(raw, false));
+  }
+
+  #[test]
+  fn comment_line_after_continuation() {
+    let raw = "cargo \\\n// c\n  +nightly \\\n  fmt";
+    let vec = collect_raw_with_continuations(raw, true);
+    assert_eq!(vec.as_ref(), &["cargo", "+nightly", "fmt"]);
+  }
+
+  #[test]
+  fn into_args_for_each_variant() {
+    let raw = r#"
+      // comment
+      printf "%s-%s" 'a b' c\ d
+    "#;
+    assert_eq!(
+      CommandRepr::Raw(raw).into_args(true),
+      ["printf", "%s-%s", "a b", "c d"]
+    );
+
+    let expected = ["cargo", "+nightly", "fmt"];
+    assert_eq!(CommandRepr::from(expected).into_args(true), expected);
+
+    let owned: CommandRepr = expected
+      .map(MiniStr::from)
+      .pipe(Vec::from)
+      .into();
+    assert_eq!(owned.into_args(false), expected);
+
+    let os: CommandRepr = expected
+      .map(OsString::from)
+      .pipe(Vec::from)
+      .into();
+    assert_eq!(os.into_args(false), expected);
+  }
+
+  #[test]
+  fn comments_respect_quotes_and_urls() {
+    let raw = r#"
+      // fetch the index
+      curl
+      http://example.com/a//b
+      --header "X-Note: // not a comment" // trailing comment
+      -d 'a // b'
+    "#;
+
+    assert_eq!(
+      collect_raw(raw, true).as_ref(),
+      &[
+        "curl",
+        "http://example.com/a//b",
+        "--header",
+        "X-Note: // not a comment",
+        "-d",
+        "a // b",
+      ]
+    );
+
+    // Nothing to strip => borrowed
+    assert!(matches!(
+      remove_comments_and_collect("echo http://x"),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn display_quotes_args() {
+    let cmd = CommandRepr::from(vec!["cargo", "run", "--", "hello world", "$HOME"]);
+    assert_eq!(cmd.to_string(), "cargo run -- 'hello world' '$HOME'");
+
+    let raw = r#"printf "%s" "a b""#;
+    assert_eq!(CommandRepr::Raw(raw).to_string(), raw);
+
+    let owned: CommandRepr = vec![MiniStr::from("echo"), "it's".into()].into();
+    assert_eq!(owned.to_string(), r#"echo "it's""#);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn os_slice_keeps_non_utf8_bytes() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let arg = OsString::from_vec(b"/opt/odd\xffdir/tool".to_vec());
+    let argv = CommandRepr::from(vec![arg.clone()]).into_os_tinyvec(true);
+    assert_eq!(argv.as_slice(), [Cow::from(arg.as_os_str())]);
+  }
+
   #[cfg(all(feature = "print_ext", feature = "re_exports_tap"))]
   #[test]
   fn doc_collect_raw() {
@@ -1,4 +1,14 @@
-use std::{io, path::PathBuf};
+use alloc::borrow::Cow;
+use std::{
+  ffi::OsString,
+  io::{self, Write},
+  path::PathBuf,
+  process::{Command, ExitStatus},
+  thread,
+  time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{fs, path::Path};
 
 use getset::{CopyGetters, Getters, Setters, WithSetters};
 use tap::{Pipe, Tap};
@@ -6,8 +16,11 @@ use tap::{Pipe, Tap};
 use crate::{
   bool_ext::BoolExt,
   os_cmd::{
-    CommandRepr, CommandSpawner, MiniStr, cow_str_into_cow_osstr,
-    process::{err_failed_to_run, run_os_cmd},
+    CommandRepr, CommandSpawner, CowOsStrVec, DecodedText, MiniStr, SpawnGuard,
+    StdioMode, collect_raw_with_continuations, cow_str_into_cow_osstr,
+    process::{
+      err_failed_to_run, err_spawn_failed, extend_env_vars, run_os_cmd_status,
+    },
     repr::TinyCmds,
   },
 };
@@ -17,7 +30,15 @@ use crate::{
 /// - command: `[cmd, args...]`
 /// - remove_comments: `remove //` (only for raw string, i.e., self.command ==
 ///   CommandRepr::Raw)
+/// - join_continuations: join lines ending in `\` before splitting (only for
+///   raw string). Comments are filtered first, then continuations are joined.
 /// - inspect_mode: Emit the command via eprintln! or log::debug!
+/// - cwd: working directory for the child process (inherits the current one
+///   when `None`)
+/// - envs: environment variables for the child process
+/// - vars: `${KEY}` placeholders substituted in each argv element (no shell
+///   involved)
+/// - dry_run: only collect and inspect the command, without executing it
 #[derive(Debug, Clone, WithSetters, Getters, Setters, CopyGetters)]
 #[getset(set = "pub", set_with = "pub", get = "pub with_prefix")]
 pub struct Runner<'a> {
@@ -28,9 +49,43 @@ pub struct Runner<'a> {
   /// Whether to strip `//`-style line comments from raw command strings.
   pub(crate) remove_comments: bool,
 
+  /// Whether to join lines ending in a shell-style `\` continuation in raw
+  /// command strings.
+  ///
+  /// See also: [collect_raw_with_continuations](super::collect_raw_with_continuations)
+  pub(crate) join_continuations: bool,
+
   /// Controls how (and whether) the command is surfaced for
   /// debugging/inspection.
   inspect_mode: RunnerInspection,
+
+  /// Working directory for the child process.
+  pub(crate) cwd: Option<PathBuf>,
+
+  /// Environment variables for the child process.
+  pub(crate) envs: Option<Box<[(MiniStr, MiniStr)]>>,
+
+  /// Clears the inherited environment before applying `envs`.
+  pub(crate) env_clear: bool,
+
+  /// Variables for `${KEY}` placeholders.
+  ///
+  /// In Phase 1 (after shlex splitting), each `${KEY}` in an argv element is
+  /// replaced with the mapped value. This is plain text substitution, no
+  /// shell is involved.
+  ///
+  /// Unknown placeholders are kept as-is, unless `strict_vars` is true.
+  pub(crate) vars: Option<Box<[(MiniStr, MiniStr)]>>,
+
+  /// When true, the run/capture methods return an error of kind
+  /// [InvalidInput](io::ErrorKind::InvalidInput) for a `${KEY}` placeholder
+  /// that is not in `vars`.
+  strict_vars: bool,
+
+  /// When true, the command is collected and inspected, but not executed.
+  ///
+  /// The run methods then report a successful (default) `ExitStatus`.
+  dry_run: bool,
 }
 
 #[derive(Debug, Clone, Default, Copy)]
@@ -46,6 +101,18 @@ pub enum RunnerInspection {
   None,
 }
 
+impl RunnerInspection {
+  /// Emits the spawner according to the inspection mode.
+  fn inspect_spawner(self, spawner: &CommandSpawner) {
+    use RunnerInspection::{LogDebug, Stderr};
+    match self {
+      LogDebug => log::debug!("{spawner:#?}"),
+      Stderr => eprintln!("{spawner:#?}"),
+      _ => {}
+    }
+  }
+}
+
 pub trait RunnableCommand<'a>: Sized
 where
   Runner<'a>: From<Self>,
@@ -62,7 +129,76 @@ where
   ///   .run()
   /// ```
   fn run(self) -> io::Result<()> {
-    Runner::from(self).run_command()
+    self.into_runner().run_command()
+  }
+
+  /// Converts the command into a [Runner], e.g., to configure `cwd`/`envs`
+  /// before running it.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::{RunnableCommand, presets::CargoFmt};
+  ///
+  /// let runner = CargoFmt::default()
+  ///   .into_runner()
+  ///   .with_dry_run(true);
+  /// assert!(runner.get_dry_run());
+  ///
+  /// runner.run()?;
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  fn into_runner(self) -> Runner<'a> {
+    Runner::from(self)
+  }
+
+  /// Returns the argv the command would run, without running it.
+  ///
+  /// See also: [CommandRepr::into_args()]
+  ///
+  /// Note: this is only the argv; per-command envs (e.g., `RUSTFLAGS` of
+  /// `CargoCmd`) are not included.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::{RunnableCommand, presets::CargoFmt};
+  ///
+  /// let args = CargoFmt::default()
+  ///   .with_nightly(false)
+  ///   .preview_args();
+  /// assert_eq!(args, ["cargo", "fmt"]);
+  /// ```
+  fn preview_args(self) -> Vec<MiniStr>
+  where
+    Self: Into<CommandRepr<'a>>,
+  {
+    // Same as the default of `Runner::remove_comments`
+    self.into().into_args(true)
+  }
+
+  /// Returns [RunnableCommand::preview_args()] as a JSON array, e.g., for a
+  /// non-Rust orchestrator.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::{RunnableCommand, presets::CargoFmt};
+  ///
+  /// let json = CargoFmt::default()
+  ///   .with_nightly(false)
+  ///   .to_json_args();
+  /// assert_eq!(json, r#"["cargo","fmt"]"#);
+  /// ```
+  #[cfg(feature = "json")]
+  fn to_json_args(self) -> String
+  where
+    Self: Into<CommandRepr<'a>>,
+  {
+    self
+      .preview_args()
+      .pipe_ref(serde_json::to_string)
+      .expect("a list of strings is always valid JSON")
   }
 
   fn into_spawner(
@@ -70,9 +206,18 @@ where
     envs: Option<Box<[(MiniStr, MiniStr)]>>,
     working_dir: Option<PathBuf>,
   ) -> CommandSpawner<'a> {
-    CommandSpawner::from(self)
-      .with_envs(envs)
-      .with_working_dir(working_dir)
+    self
+      .into_runner()
+      .pipe(CommandSpawner::from)
+      // `None` keeps the values configured on the Runner.
+      .pipe(|x| match envs {
+        Some(_) => x.with_envs(envs),
+        _ => x,
+      })
+      .pipe(|x| match working_dir {
+        Some(_) => x.with_working_dir(working_dir),
+        _ => x,
+      })
   }
 }
 
@@ -80,53 +225,674 @@ impl<'a> RunnableCommand<'a> for Runner<'a> {}
 
 impl Runner<'_> {
   /// see also: [RunnableCommand::run()]
+  ///
+  /// A non-zero exit status is converted into an error
+  /// ([OsCmdError::NonZeroExit](super::OsCmdError::NonZeroExit)). Use
+  /// [Runner::run_status()] to inspect the exit code instead.
   pub fn run_command(self) -> io::Result<()> {
+    let (program, status) = self.run_program()?;
+
+    status
+      .success()
+      .then_ok_or_else(|| err_failed_to_run(program, status))
+  }
+
+  /// Runs the command via [Runner::run_command()], retrying on failure.
+  ///
+  /// - `attempts`: the maximum number of tries (`0` is treated as `1`).
+  /// - `backoff`: fixed sleep between two tries.
+  ///
+  /// Returns `Ok(())` on the first success, otherwise the error of the last
+  /// try.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use std::time::Duration;
+  ///
+  /// use testutils::os_cmd::{RunnableCommand, presets::CargoFmt};
+  ///
+  /// CargoFmt::default()
+  ///   .into_runner()
+  ///   .with_dry_run(true)
+  ///   .run_with_retries(3, Duration::from_millis(100))?;
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn run_with_retries(
+    self,
+    attempts: usize,
+    backoff: Duration,
+  ) -> io::Result<()> {
+    let attempts = attempts.max(1);
+
+    for n in 1..attempts {
+      match self.clone().run_command() {
+        Ok(()) => return Ok(()),
+        Err(e) => log::warn!("Attempt {n}/{attempts} failed: {e}"),
+      }
+      thread::sleep(backoff)
+    }
+    self.run_command()
+  }
+
+  /// Executes the command and returns its raw `ExitStatus`.
+  ///
+  /// Unlike [Runner::run_command()], a non-zero exit status is **not**
+  /// treated as an error. Spawn failures are still reported as
+  /// [OsCmdError](super::OsCmdError).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::Runner, tap::Pipe};
+  ///
+  /// let status = ["sh", "-c", "exit 3"]
+  ///   .pipe(Runner::from)
+  ///   .run_status()?;
+  /// assert_eq!(status.code(), Some(3));
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn run_status(self) -> io::Result<ExitStatus> {
+    self
+      .run_program()
+      .map(|(_, status)| status)
+  }
+
+  /// Runs the command via [Runner::run_status()] and returns how long it
+  /// took, e.g., to profile build steps.
+  ///
+  /// The exit status is not inspected, so a non-zero exit still returns the
+  /// elapsed time. Spawn failures are reported as errors.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::Runner, tap::Pipe};
+  ///
+  /// let elapsed = ["sh", "-c", "exit 1"]
+  ///   .pipe(Runner::from)
+  ///   .run_timed()?;
+  /// eprintln!("took {elapsed:?}");
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn run_timed(self) -> io::Result<Duration> {
+    let start = Instant::now();
+    self.run_status()?;
+    Ok(start.elapsed())
+  }
+
+  /// Runs the command and asserts that it exits successfully, e.g., in tests
+  /// instead of `assert!(runner.run_status()?.success())`.
+  ///
+  /// On a non-zero exit, the error (kind `Other`) contains the full command
+  /// line (via [CommandRepr]'s `Display`) and the exit status.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::Runner, tap::Pipe};
+  ///
+  /// let err = ["sh", "-c", "exit 3"]
+  ///   .pipe(Runner::from)
+  ///   .assert_success()
+  ///   .expect_err("non-zero exit");
+  /// assert!(
+  ///   err
+  ///     .to_string()
+  ///     .contains("sh -c 'exit 3'")
+  /// );
+  /// # }
+  /// ```
+  pub fn assert_success(self) -> io::Result<()> {
+    let cmd = self.command.to_string();
+    let status = self.run_status()?;
+
+    status
+      .success()
+      .then_ok_or_else(|| {
+        io::Error::other(format!("Command `{cmd}` did not succeed: {status}"))
+      })
+  }
+
+  /// Pipes the stdout of `self` into the stdin of `next`, i.e., `self | next`.
+  ///
+  /// Both commands are spawned before waiting, so large outputs do not
+  /// deadlock. Returns the exit status of `next`; the status of `self` is
+  /// only waited on (to avoid zombies), not inspected.
+  ///
+  /// This is two-stage only. For longer pipelines, use a shell (e.g.,
+  /// `sh -c "a | b | c"`).
+  ///
+  /// Notes:
+  ///
+  /// - `next.stdin_data` is ignored, since its stdin is the pipe.
+  /// - If either runner has `dry_run` enabled, nothing is spawned and a
+  ///   successful (default) `ExitStatus` is returned.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{os_cmd::Runner, tap::Pipe};
+  ///
+  /// let status = ["echo", "hello"]
+  ///   .pipe(Runner::from)
+  ///   .pipe_to(Runner::from(["grep", "-q", "hello"]))?;
+  /// assert!(status.success());
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn pipe_to(self, next: Runner) -> io::Result<ExitStatus> {
+    let dry_run = self.dry_run || next.dry_run;
+
+    let (mut first_cmd, first_stdin) =
+      self.into_inspected_command(StdioMode::Piped)?;
+    let (mut second_cmd, _) = next.into_inspected_command(StdioMode::Inherit)?;
+
+    if dry_run {
+      return Ok(ExitStatus::default());
+    }
+
+    // Guarded, so that on any error below, both children are killed and
+    // reaped instead of leaking.
+    let mut first = first_cmd
+      .spawn()
+      .map_err(err_spawn_failed(first_cmd.get_program()))?
+      .pipe(SpawnGuard::new);
+    let pipe = first
+      .stdout
+      .take()
+      .ok_or_else(|| io::Error::other("Failed to access child's stdout."))?;
+
+    let mut second = second_cmd
+      .stdin(pipe)
+      .spawn()
+      .map_err(err_spawn_failed(second_cmd.get_program()))?
+      .pipe(SpawnGuard::new);
+
+    // Write stdin only after `next` is reading, so a full stdout pipe cannot
+    // block `self`. Dropping the handle closes it.
+    if let Some(data) = first_stdin {
+      first
+        .stdin
+        .take()
+        .ok_or_else(|| {
+          io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Failed to access child's stdin.",
+          )
+        })?
+        .write_all(data)?
+    }
+    first.wait()?;
+    second.wait()
+  }
+
+  /// Runs the command and returns its stdout as [DecodedText].
+  ///
+  /// The command is inspected (see [RunnerInspection]) before running, then
+  /// spawned via [CommandSpawner] with stdout piped; stderr is inherited.
+  ///
+  /// - A non-zero exit status is converted into an error, like
+  ///   [Runner::run_command()].
+  /// - With `dry_run`, nothing is spawned and an empty text is returned.
+  ///
+  /// See also: [CommandSpawner::capture_stdout()]
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{
+  ///   os_cmd::{Runner, RunnerInspection},
+  ///   tap::Pipe,
+  /// };
+  ///
+  /// let text = ["echo", "hello"]
+  ///   .pipe(Runner::from)
+  ///   .with_inspect_mode(RunnerInspection::None)
+  ///   .capture()?;
+  /// assert_eq!(text.trim_ascii_end(), "hello");
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn capture(self) -> io::Result<DecodedText> {
+    let dry_run = self.dry_run;
+    let spawner = self.into_inspected_spawner()?;
+
+    if dry_run {
+      return Ok(DecodedText::default());
+    }
+
+    let program = spawner
+      .get_argv()
+      .first()
+      .map(|x| x.to_os_string())
+      .unwrap_or_default();
+
+    let (text, status) = spawner.capture_stdout_with_status()?;
+    status
+      .success()
+      .then_ok_or_else(|| err_failed_to_run(program, status))?;
+    Ok(text)
+  }
+
+  /// Runs the command and returns `(stdout, stderr, exit_status)`.
+  ///
+  /// Both streams are piped and drained concurrently. Unlike
+  /// [Runner::capture()], a non-zero exit status is **not** treated as an
+  /// error, so the caller can assert on all three.
+  ///
+  /// With `dry_run`, nothing is spawned and `("", "", success)` is returned.
+  ///
+  /// `stdin_data` is written on a separate thread while the output is
+  /// drained, so large payloads do not deadlock.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::{
+  ///   os_cmd::{Runner, RunnerInspection},
+  ///   tap::Pipe,
+  /// };
+  ///
+  /// let (stdout, stderr, status) = ["sh", "-c", "echo out; echo err >&2"]
+  ///   .pipe(Runner::from)
+  ///   .with_inspect_mode(RunnerInspection::None)
+  ///   .capture_all()?;
+  /// assert_eq!(stdout.data(), "out\n");
+  /// assert_eq!(stderr.data(), "err\n");
+  /// assert!(status.success());
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  pub fn capture_all(self) -> io::Result<(DecodedText, DecodedText, ExitStatus)> {
+    let dry_run = self.dry_run;
+    let spawner = self.into_inspected_spawner()?;
+
+    if dry_run {
+      return Ok(Default::default());
+    }
+
+    spawner
+      .capture_raw_output(true, true)?
+      .pipe(|o| {
+        (
+          DecodedText::from_vec(o.stdout),
+          DecodedText::from_vec(o.stderr),
+          o.status,
+        )
+      })
+      .pipe(Ok)
+  }
+
+  /// Executes the command, returning `(program, exit_status)`.
+  fn run_program(self) -> io::Result<(OsString, ExitStatus)> {
     use RunnerInspection::{LogDebug, Stderr};
-    let Self { inspect_mode, .. } = self;
+    self.check_vars()?;
+    let Self {
+      inspect_mode,
+      dry_run,
+      ..
+    } = self;
 
-    if self.get_stdin_data().is_some() {
-      return self
+    if self.requires_spawner() {
+      let spawner = self
         .pipe(CommandSpawner::from)
-        .tap(|x| match inspect_mode {
-          LogDebug => log::debug!("{x:#?}"),
-          Stderr => eprintln!("{x:#?}"),
-          _ => {}
-        })
+        .tap(|x| inspect_mode.inspect_spawner(x));
+
+      let program = spawner
+        .get_argv()
+        .first()
+        .map(|x| x.to_os_string())
+        .unwrap_or_default();
+
+      if dry_run {
+        return Ok((program, ExitStatus::default()));
+      }
+
+      return spawner
         .spawn()?
-        .wait()?
-        .success()
-        .then_ok_or_else(|| err_failed_to_run(None));
+        .wait()
+        .map(|status| (program, status));
     }
 
     // Phase 1: Command collection
-    self
-      .into_tinyvec()
+    let argv = self
+      .into_os_tinyvec()
       // Phase 2: Command inspection
       .tap(|v| match inspect_mode {
         Stderr => eprintln!("{v:?}"),
         LogDebug => log::debug!("{v:?}"),
         _ => {}
-      })
-      // Phase 3: OS command execution
+      });
+
+    if dry_run {
+      let program = argv
+        .first()
+        .map(|x| x.to_os_string())
+        .unwrap_or_default();
+      return Ok((program, ExitStatus::default()));
+    }
+
+    // Phase 3: OS command execution
+    argv
       .into_iter()
-      .map(cow_str_into_cow_osstr)
-      .pipe(run_os_cmd)
+      .pipe(run_os_cmd_status)
   }
 }
 
 impl<'a> Runner<'a> {
+  /// Builds a Runner from "one argv token per line" contents, e.g., a command
+  /// file.
+  ///
+  /// - Each line is trimmed, and then taken literally as one token (no shlex
+  ///   splitting), so whitespace inside a token (e.g., a path with spaces) is
+  ///   preserved.
+  /// - Empty lines and `//` comment lines are skipped.
+  ///
+  /// The tokens are stored as [CommandRepr::OwnedSlice].
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use testutils::os_cmd::{CommandRepr, Runner};
+  ///
+  /// let runner = Runner::from_command_lines(
+  ///   r#"
+  ///   // list a directory
+  ///   ls
+  ///   /tmp/my dir
+  ///   "#,
+  /// );
+  ///
+  /// assert_eq!(runner.command.into_args(false), ["ls", "/tmp/my dir"]);
+  /// ```
+  pub fn from_command_lines(contents: &str) -> Self {
+    contents
+      .lines()
+      .map(str::trim_ascii)
+      .filter(|line| !(line.is_empty() || line.starts_with("//")))
+      .map(MiniStr::from)
+      .collect::<Box<[_]>>()
+      .pipe(CommandRepr::OwnedSlice)
+      .pipe(Runner::from)
+  }
+
+  /// Inspects the command, then builds it with the given stdout mode.
+  ///
+  /// See also: [CommandSpawner::into_command()]
+  fn into_inspected_command(
+    self,
+    stdout: StdioMode,
+  ) -> io::Result<(Command, Option<&'a [u8]>)> {
+    self.check_vars()?;
+    let mode = self.inspect_mode;
+    self
+      .pipe(CommandSpawner::from)
+      .with_stdout(stdout)
+      .tap(|x| mode.inspect_spawner(x))
+      .into_command()
+  }
+
+  /// Converts the Runner into a [CommandSpawner], then inspects it.
+  fn into_inspected_spawner(self) -> io::Result<CommandSpawner<'a>> {
+    self.check_vars()?;
+    let mode = self.inspect_mode;
+    self
+      .pipe(CommandSpawner::from)
+      .tap(|x| mode.inspect_spawner(x))
+      .pipe(Ok)
+  }
+
+  /// Returns true if the command can't be run via the plain
+  /// [run_os_cmd](super::run) path (e.g., stdin data, working directory, or
+  /// environment variables are configured).
+  fn requires_spawner(&self) -> bool {
+    self.stdin_data.is_some()
+      || self.cwd.is_some()
+      || self.envs.is_some()
+      || self.env_clear
+  }
+
+  /// Appends a single environment variable.
+  ///
+  /// Unlike `with_envs`, this keeps the previously configured variables.
+  pub fn with_env<K, V>(self, key: K, value: V) -> Self
+  where
+    K: Into<MiniStr>,
+    V: Into<MiniStr>,
+  {
+    self.extend_envs([(key, value)])
+  }
+
+  /// Appends multiple environment variables, keeping the previously
+  /// configured ones.
+  pub fn extend_envs<I, K, V>(mut self, iter: I) -> Self
+  where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<MiniStr>,
+    V: Into<MiniStr>,
+  {
+    self.envs = extend_env_vars(self.envs.take(), iter);
+    self
+  }
+
   /// - Raw(&str) => [collect_raw](super::collect_raw) => command vec
   /// - Slice(Box<[&str]>) => `TinyVec<[Cow<&str>]>`
   /// - OwnedSlice(Box<[compact_str::CompactString]>) =>
   ///   `TinyVec<[Cow<String>]>`
+  ///
+  /// If `join_continuations` is true, Raw goes through
+  /// [collect_raw_with_continuations](super::collect_raw_with_continuations)
+  /// instead.
+  ///
+  /// `${KEY}` placeholders are then substituted with `vars` (if any).
   pub fn into_tinyvec(self) -> TinyCmds<'a> {
     let Self {
       command,
       remove_comments,
+      join_continuations,
+      vars,
       ..
     } = self;
 
-    command.into_tinyvec(remove_comments)
+    let argv = match command {
+      CommandRepr::Raw(raw) if join_continuations => {
+        collect_raw_with_continuations(raw, remove_comments)
+      }
+      c => c.into_tinyvec(remove_comments),
+    };
+
+    match vars {
+      Some(vars) => argv
+        .into_iter()
+        .map(|arg| substitute_vars(arg, &vars))
+        .collect(),
+      _ => argv,
+    }
+  }
+
+  /// Like [Runner::into_tinyvec()], but keeps `CommandRepr::OsSlice` items
+  /// as-is instead of converting them lossily.
+  ///
+  /// For OsSlice, `vars` are only substituted in valid UTF-8 items.
+  pub fn into_os_tinyvec(self) -> CowOsStrVec<'a, 9> {
+    match self.command {
+      CommandRepr::OsSlice(items) if self.vars.is_some() => {
+        let vars = self.vars.unwrap_or_default();
+        items
+          .into_iter()
+          .map(|x| match x.into_string() {
+            Ok(s) => substitute_vars(s.into(), &vars)
+              .into_owned()
+              .into(),
+            Err(os) => os,
+          })
+          .map(Cow::from)
+          .collect()
+      }
+      CommandRepr::OsSlice(_) => self
+        .command
+        .into_os_tinyvec(self.remove_comments),
+      _ => self
+        .into_tinyvec()
+        .into_iter()
+        .map(cow_str_into_cow_osstr)
+        .collect(),
+    }
+  }
+
+  /// Writes the command to a script file, e.g., to rerun it by hand when
+  /// debugging CI.
+  ///
+  /// The argv is collected as in Phase 1 (see [Runner::into_tinyvec()]), then
+  /// each token is POSIX shell-quoted (see [join_quoted](super::join_quoted))
+  /// and written as a single line after a `#!/bin/sh` shebang. The file mode
+  /// is set to `0o755`.
+  ///
+  /// Only available on unix, since `cmd.exe` does not understand POSIX
+  /// quoting.
+  ///
+  /// Note: only the argv is written; `cwd`, `envs` and `stdin_data` are not.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// # #[cfg(unix)] {
+  /// use testutils::os_cmd::Runner;
+  ///
+  /// let path = std::env::temp_dir().join("testutils_write_script_doc.sh");
+  /// Runner::from("cargo +nightly fmt").write_script(&path)?;
+  ///
+  /// let script = std::fs::read_to_string(&path)?;
+  /// assert!(script.ends_with("cargo +nightly fmt\n"));
+  /// # std::fs::remove_file(path)?;
+  /// # }
+  /// # Ok::<(), std::io::Error>(())
+  /// ```
+  #[cfg(unix)]
+  pub fn write_script(&self, path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::os_cmd::join_quoted;
+
+    let argv = self
+      .clone()
+      .into_tinyvec()
+      .into_iter()
+      .map(MiniStr::from)
+      .collect::<Vec<_>>();
+
+    fs::write(path, format!("#!/bin/sh\n{}\n", join_quoted(&argv)))?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+  }
+
+  /// With `strict_vars`, returns an error for the first `${KEY}` placeholder
+  /// that is not in `vars`.
+  fn check_vars(&self) -> io::Result<()> {
+    if !self.strict_vars {
+      return Ok(());
+    }
+    let vars = self
+      .vars
+      .as_deref()
+      .unwrap_or_default();
+
+    let unknown = self
+      .clone()
+      .with_vars(None)
+      .into_tinyvec()
+      .iter()
+      .find_map(|arg| {
+        let mut unknown = None;
+        replace_placeholders(arg.as_ref().into(), |key| {
+          let value = lookup_var(vars, key);
+          if value.is_none() && unknown.is_none() {
+            unknown = Some(MiniStr::from(key));
+          }
+          value
+        });
+        unknown
+      });
+
+    match unknown {
+      Some(key) => io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Unknown variable: ${{{key}}}"),
+      )
+      .pipe(Err),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// Looks up `key` in `vars`; the last matching entry wins.
+fn lookup_var<'v>(vars: &'v [(MiniStr, MiniStr)], key: &str) -> Option<&'v str> {
+  vars
+    .iter()
+    .rev()
+    .find(|(k, _)| k == key)
+    .map(|(_, v)| v.as_str())
+}
+
+/// Replaces each `${KEY}` in `arg` with the mapped value; unknown placeholders
+/// are kept as-is.
+fn substitute_vars<'a>(
+  arg: Cow<'a, str>,
+  vars: &[(MiniStr, MiniStr)],
+) -> Cow<'a, str> {
+  replace_placeholders(arg, |key| lookup_var(vars, key))
+}
+
+/// Scans `s` for `${KEY}` placeholders, and replaces them with `f(KEY)`.
+///
+/// - `None` => the placeholder is kept.
+/// - An unterminated `${` is kept as well.
+///
+/// Returns the input as is if nothing was replaced.
+fn replace_placeholders<'a, 'v, F>(s: Cow<'a, str>, mut f: F) -> Cow<'a, str>
+where
+  F: FnMut(&str) -> Option<&'v str>,
+{
+  if !s.contains("${") {
+    return s;
+  }
+
+  let mut out = String::with_capacity(s.len());
+  let mut replaced = false;
+  let mut rest = s.as_ref();
+
+  while let Some(start) = rest.find("${") {
+    let (head, tail) = rest.split_at(start);
+    out.push_str(head);
+
+    let Some(end) = tail.find('}') else {
+      rest = tail;
+      break;
+    };
+
+    let placeholder = &tail[..=end];
+    match f(&placeholder[2..end]) {
+      Some(value) => {
+        out.push_str(value);
+        replaced = true
+      }
+      _ => out.push_str(placeholder),
+    }
+    rest = &tail[end + 1..];
+  }
+  out.push_str(rest);
+
+  match replaced {
+    true => out.into(),
+    _ => s,
   }
 }
 
@@ -137,15 +903,30 @@ impl Default for Runner<'_> {
   /// Runner {
   ///     command: CommandRepr::Raw("cargo"),
   ///     remove_comments: true,
+  ///     join_continuations: false,
   ///     inspect_mode: RunnerInspection::Stderr,
+  ///     stdin_data: None,
+  ///     cwd: None,
+  ///     envs: None,
+  ///     env_clear: false,
+  ///     vars: None,
+  ///     strict_vars: false,
+  ///     dry_run: false,
   /// }
   /// ```
   fn default() -> Self {
     Self {
       command: CommandRepr::default(),
       remove_comments: true,
+      join_continuations: false,
       inspect_mode: RunnerInspection::default(),
       stdin_data: None,
+      cwd: None,
+      envs: None,
+      env_clear: false,
+      vars: None,
+      strict_vars: false,
+      dry_run: false,
     }
   }
 }
@@ -172,4 +953,453 @@ mod tests {
   fn show_default_runner() {
     Runner::default().pipe(|x| crate::dbg!(x));
   }
+
+  #[test]
+  #[cfg(feature = "json")]
+  fn cargo_fmt_json_args() {
+    use crate::os_cmd::presets::CargoFmt;
+
+    let json = CargoFmt::default().to_json_args();
+    let args: Vec<String> = serde_json::from_str(&json).expect("JSON array");
+    assert_eq!(args, ["cargo", "+nightly", "fmt"]);
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn run_in_cwd() -> io::Result<()> {
+    let dir = std::env::temp_dir().canonicalize()?;
+
+    let stdout = "pwd"
+      .pipe(Runner::from)
+      .with_cwd(Some(dir.clone()))
+      .pipe(CommandSpawner::from)
+      .capture_stdout()?;
+    assert_eq!(stdout.trim_ascii_end(), dir.to_string_lossy());
+
+    "ls"
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_cwd(Some(dir))
+      .run()
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn runner_with_env() -> io::Result<()> {
+    let runner = ["sh", "-c", r#"printf "%s" "$RUNNER_ENV""#]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_env("RUNNER_ENV", "from-runner");
+
+    let stdout = runner
+      .clone()
+      .pipe(CommandSpawner::from)
+      .capture_stdout()?;
+    assert_eq!(stdout.data(), "from-runner");
+
+    // `None` doesn't discard the env vars configured on the Runner.
+    let stdout = runner
+      .into_spawner(None, None)
+      .capture_stdout()?;
+    assert_eq!(stdout.data(), "from-runner");
+
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn capture_all_with_failure() -> io::Result<()> {
+    let (stdout, stderr, status) =
+      ["sh", "-c", "printf out; printf err >&2; exit 4"]
+        .pipe(Runner::from)
+        .with_inspect_mode(RunnerInspection::None)
+        .capture_all()?;
+
+    assert_eq!(stdout.data(), "out");
+    assert_eq!(stderr.data(), "err");
+    assert_eq!(status.code(), Some(4));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn capture_all_with_large_stdin() -> io::Result<()> {
+    // Much larger than a pipe buffer, in both directions.
+    let data = "0123456789abcdef\n".repeat(64 * 1024);
+
+    let (stdout, stderr, status) = ["sh", "-c", "tee /dev/stderr"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_stdin_data(Some(data.as_bytes()))
+      .capture_all()?;
+
+    assert!(status.success());
+    assert_eq!(stdout.data().len(), data.len());
+    assert_eq!(stderr.data().len(), data.len());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn retries_until_success() -> io::Result<()> {
+    let counter = std::env::temp_dir().join("testutils_runner_retries.txt");
+    let _ = fs::remove_file(&counter);
+
+    // Appends a line per try, and fails until the 3rd one.
+    let script = format!(
+      r#"echo x >> "{0}"; test "$(wc -l < "{0}")" -ge 3"#,
+      counter.display()
+    );
+    let runner = ["sh", "-c", &script]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None);
+
+    runner
+      .clone()
+      .run_with_retries(2, Duration::ZERO)
+      .expect_err("only 2 tries");
+    fs::remove_file(&counter)?;
+
+    runner.run_with_retries(5, Duration::from_millis(1))?;
+    assert_eq!(
+      fs::read_to_string(&counter)?
+        .lines()
+        .count(),
+      3
+    );
+
+    fs::remove_file(counter)
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn run_timed_sleep() -> io::Result<()> {
+    let elapsed = ["sleep", "0.1"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .run_timed()?;
+    assert!(elapsed >= Duration::from_millis(100), "{elapsed:?}");
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn assert_success_reports_argv() -> io::Result<()> {
+    ["true"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .assert_success()?;
+
+    let err = ["false", "--flag"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .assert_success()
+      .expect_err("`false` exits with 1");
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert!(
+      err
+        .to_string()
+        .contains("`false --flag`"),
+      "{err}"
+    );
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn run_status_exit_code() -> io::Result<()> {
+    let runner = ["sh", "-c", "exit 3"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None);
+
+    let status = runner.clone().run_status()?;
+    assert_eq!(status.code(), Some(3));
+
+    // `run()` still converts a non-zero exit into an error.
+    assert!(runner.run().is_err());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn capture_echo_hello() -> io::Result<()> {
+    let text = ["echo", "hello"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::LogDebug)
+      .capture()?;
+    assert_eq!(text.trim_ascii_end(), "hello");
+
+    let err = ["sh", "-c", "echo partial; exit 1"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .capture()
+      .expect_err("non-zero exit");
+    assert!(matches!(
+      into_os_cmd_error(err),
+      crate::os_cmd::OsCmdError::NonZeroExit { .. }
+    ));
+
+    let text = "false"
+      .pipe(Runner::from)
+      .with_dry_run(true)
+      .capture()?;
+    assert!(text.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn pipe_echo_into_cat() -> io::Result<()> {
+    let out = std::env::temp_dir().join("testutils_runner_pipe_to.txt");
+    fn quiet(r: Runner) -> Runner {
+      r.with_inspect_mode(RunnerInspection::None)
+    }
+
+    // echo hello | cat > out
+    let status = ["echo", "hello"]
+      .pipe(Runner::from)
+      .pipe(quiet)
+      .pipe_to(
+        ["sh", "-c", r#"cat > "$0""#, &out.to_string_lossy()]
+          .pipe(Runner::from)
+          .pipe(quiet),
+      )?;
+    assert!(status.success());
+    assert_eq!(std::fs::read_to_string(&out)?, "hello\n");
+    std::fs::remove_file(out)?;
+
+    // The status of the second command is returned.
+    let status = ["echo", "hello"]
+      .pipe(Runner::from)
+      .pipe(quiet)
+      .pipe_to(
+        ["grep", "-q", "world"]
+          .pipe(Runner::from)
+          .pipe(quiet),
+      )?;
+    assert_eq!(status.code(), Some(1));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn pipe_to_spawn_failure_kills_first() {
+    use crate::os_cmd::OsCmdError;
+
+    let start = Instant::now();
+    let err = ["sleep", "30"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .pipe_to(
+        ["/nonexistent/testutils-program"]
+          .pipe(Runner::from)
+          .with_inspect_mode(RunnerInspection::None),
+      )
+      .expect_err("the second program does not exist");
+
+    assert!(matches!(
+      into_os_cmd_error(err),
+      OsCmdError::SpawnFailed { program, .. } if program == "/nonexistent/testutils-program"
+    ));
+    // `sleep` was killed, not waited on for 30s.
+    assert!(start.elapsed() < Duration::from_secs(10));
+  }
+
+  /// Recovers the [OsCmdError] wrapped in an `io::Error`.
+  fn into_os_cmd_error(err: io::Error) -> crate::os_cmd::OsCmdError {
+    err
+      .into_inner()
+      .and_then(|e| e.downcast().ok())
+      .map(|e| *e)
+      .expect("OsCmdError")
+  }
+
+  #[test]
+  fn empty_command_error() {
+    use crate::os_cmd::OsCmdError;
+
+    let err = Runner::from("  // comment only")
+      .with_remove_comments(true)
+      .with_inspect_mode(RunnerInspection::None)
+      .run_status()
+      .expect_err("empty command");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(matches!(into_os_cmd_error(err), OsCmdError::EmptyCommand));
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn non_zero_exit_error() {
+    use crate::os_cmd::OsCmdError;
+
+    let err = ["sh", "-c", "exit 5"]
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .run()
+      .expect_err("non-zero exit");
+
+    match into_os_cmd_error(err) {
+      OsCmdError::NonZeroExit { program, status } => {
+        assert_eq!(program, "sh");
+        assert_eq!(status.code(), Some(5));
+      }
+      e => panic!("unexpected variant: {e:?}"),
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn run_os_string_program() -> io::Result<()> {
+    let runner = ["/bin/sh", "-c", "exit 4"]
+      .map(OsString::from)
+      .pipe(Vec::from)
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None);
+
+    assert!(matches!(runner.command, CommandRepr::OsSlice(_)));
+    assert_eq!(runner.run_status()?.code(), Some(4));
+    Ok(())
+  }
+
+  #[test]
+  fn runner_from_command_file() -> io::Result<()> {
+    let file = std::env::temp_dir().join("testutils_runner_command_lines.txt");
+    std::fs::write(
+      &file,
+      "// print a path that contains spaces\n\nprintf\n%s\n  /opt/my tools/bin  \n",
+    )?;
+
+    let runner = std::fs::read_to_string(&file)?
+      .pipe_deref(Runner::from_command_lines)
+      .with_inspect_mode(RunnerInspection::None);
+    std::fs::remove_file(file)?;
+
+    assert_eq!(
+      runner
+        .command
+        .clone()
+        .into_args(false),
+      ["printf", "%s", "/opt/my tools/bin"]
+    );
+
+    #[cfg(unix)]
+    {
+      let stdout = runner
+        .pipe(CommandSpawner::from)
+        .capture_stdout()?;
+      assert_eq!(stdout.data(), "/opt/my tools/bin");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn join_continuations_in_raw_command() {
+    let raw = r#"
+      cargo \
+      // select the toolchain
+        +nightly \
+        fmt
+    "#;
+
+    let argv = Runner::from(raw)
+      .with_join_continuations(true)
+      .into_tinyvec();
+    assert_eq!(argv.as_ref(), &["cargo", "+nightly", "fmt"]);
+  }
+
+  #[test]
+  fn substitute_known_and_unknown_vars() -> io::Result<()> {
+    let runner = Runner::from(r#"cargo build "--package=${PKG}" ${PROFILE}"#)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_vars(Some([("PKG".into(), "testutils".into())].into()));
+
+    let argv = runner.clone().into_tinyvec();
+    assert_eq!(
+      argv.as_ref(),
+      &["cargo", "build", "--package=testutils", "${PROFILE}"]
+    );
+
+    let err = runner
+      .with_strict_vars(true)
+      .with_dry_run(true)
+      .run()
+      .expect_err("unknown placeholder");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(
+      err
+        .to_string()
+        .contains("${PROFILE}")
+    );
+
+    // OsSlice items are substituted as well.
+    let argv = vec![OsString::from("echo"), OsString::from("${PKG}-${PKG}")]
+      .pipe(Runner::from)
+      .with_vars(Some([("PKG".into(), "a".into())].into()))
+      .into_os_tinyvec();
+    assert_eq!(argv[1], std::ffi::OsStr::new("a-a"));
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn write_script_is_executable() -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join("testutils_runner_write_script.sh");
+    Runner::from(r#"printf "%s" "hello world" // greet"#).write_script(&path)?;
+
+    let script = fs::read_to_string(&path)?;
+    assert_eq!(script, "#!/bin/sh\nprintf '%s' 'hello world'\n");
+
+    let mode = fs::metadata(&path)?
+      .permissions()
+      .mode();
+    assert_eq!(mode & 0o777, 0o755);
+
+    // Run it via `sh` rather than exec'ing it: another test thread forking at
+    // the same time may still hold the write fd, which fails with ETXTBSY.
+    let out = CommandSpawner::from([
+      "sh",
+      path
+        .to_str()
+        .expect("utf-8 path"),
+    ])
+    .capture_stdout()?;
+    assert_eq!(out.data(), "hello world");
+
+    fs::remove_file(path)
+  }
+
+  #[test]
+  fn dry_run_skips_execution() -> io::Result<()> {
+    let runner = "testutils-bogus-program --flag"
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_dry_run(true);
+
+    runner.clone().run()?;
+    assert!(
+      runner
+        .clone()
+        .run_status()?
+        .success()
+    );
+
+    // The spawner path (e.g., with env vars) is skipped as well.
+    runner
+      .with_env("FOO", "bar")
+      .run()
+  }
+
+  #[test]
+  fn run_in_missing_cwd() {
+    let dir = std::env::temp_dir().join("testutils-missing-cwd-dir");
+
+    let res = "cargo --version"
+      .pipe(Runner::from)
+      .with_inspect_mode(RunnerInspection::None)
+      .with_cwd(Some(dir))
+      .run();
+    assert!(res.is_err());
+  }
 }
@@ -0,0 +1,126 @@
+use core::ops::{Deref, DerefMut};
+use std::process::Child;
+
+/// A [Child] that is killed (and reaped) when dropped.
+///
+/// This is similar to tokio's `kill_on_drop(true)`: if a test panics after
+/// spawning a long-running process, the process does not leak.
+///
+/// Call [SpawnGuard::into_inner()] to disarm it.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(unix)] {
+/// use testutils::{os_cmd::CommandSpawner, tap::Pipe};
+///
+/// let mut guard = "sleep 30"
+///   .pipe(CommandSpawner::from)
+///   .spawn_guarded()?;
+/// assert!(guard.try_wait()?.is_none());
+///
+/// // `sleep` is killed here.
+/// drop(guard);
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SpawnGuard(Option<Child>);
+
+impl SpawnGuard {
+  pub const fn new(child: Child) -> Self {
+    Self(Some(child))
+  }
+
+  /// Disarms the guard and returns the child; it will no longer be killed on
+  /// drop.
+  pub fn into_inner(mut self) -> Child {
+    self
+      .0
+      .take()
+      .expect("SpawnGuard always holds a child until dropped")
+  }
+}
+
+impl From<Child> for SpawnGuard {
+  fn from(child: Child) -> Self {
+    Self::new(child)
+  }
+}
+
+impl Deref for SpawnGuard {
+  type Target = Child;
+
+  fn deref(&self) -> &Self::Target {
+    self
+      .0
+      .as_ref()
+      .expect("SpawnGuard always holds a child until dropped")
+  }
+}
+
+impl DerefMut for SpawnGuard {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self
+      .0
+      .as_mut()
+      .expect("SpawnGuard always holds a child until dropped")
+  }
+}
+
+impl Drop for SpawnGuard {
+  fn drop(&mut self) {
+    let Some(mut child) = self.0.take() else {
+      return;
+    };
+
+    // Already exited: just reap it.
+    if let Ok(Some(_)) = child.try_wait() {
+      return;
+    }
+
+    if let Err(e) = child.kill() {
+      log::warn!("Failed to kill child process {}: {e}", child.id());
+    }
+    // Reap the process so it does not linger as a zombie.
+    let _ = child.wait();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io;
+
+  use tap::Pipe;
+
+  use crate::os_cmd::CommandSpawner;
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn kill_on_drop() -> io::Result<()> {
+    let guard = "sleep 30"
+      .pipe(CommandSpawner::from)
+      .spawn_guarded()?;
+    let proc_dir = format!("/proc/{}", guard.id());
+    assert!(std::fs::exists(&proc_dir)?);
+
+    drop(guard);
+    // Killed and reaped: no zombie entry is left behind.
+    assert!(!std::fs::exists(&proc_dir)?);
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn into_inner_disarms() -> io::Result<()> {
+    let mut child = "sleep 30"
+      .pipe(CommandSpawner::from)
+      .spawn_guarded()?
+      .into_inner();
+
+    assert!(child.try_wait()?.is_none());
+    child.kill()?;
+    child.wait()?;
+    Ok(())
+  }
+}
@@ -4,5 +4,7 @@ pub use normal::dbg;
 
 #[cfg(feature = "std")]
 pub mod buf_lock;
+#[cfg(feature = "std")]
+pub use buf_lock::{write_stderr_all, write_stdout_all};
 
 mod macros;
@@ -12,12 +12,18 @@ also *locked*, which is a common pattern for high-throughput output:
 
 - The returned writers are **buffered**: output may not appear immediately.
   Call [`.flush()`](std::io::Write::flush) when you need timely output
-  (e.g., prompts/progress).
+  (e.g., prompts/progress), or use the `*_guarded()` variants, which flush
+  on `Drop` via [FlushOnDrop].
 - The underlying lock is held for as long as the writer value is alive. Keep
   the lifetime short if other threads also write to stdio.
 */
 // ===========================
-use std::io::{self, BufWriter};
+use core::ops::{Deref, DerefMut};
+use std::io::{self, BufWriter, Write};
+
+/// The buffer capacity used by [buf_stdout()] and [buf_stderr()] (8 KiB, the
+/// same as `BufWriter::new`).
+pub const DEFAULT_BUF_CAPACITY: usize = 8 * 1024;
 
 /// A buffered, locked handle to standard output.
 ///
@@ -37,7 +43,15 @@ pub type BufStderr = BufWriter<io::StderrLock<'static>>;
 /// `println!` calls would otherwise lock and write frequently.
 #[inline]
 pub fn buf_stdout() -> BufStdout {
-  BufWriter::new(io::stdout().lock())
+  buf_stdout_with_capacity(DEFAULT_BUF_CAPACITY)
+}
+
+/// Like [buf_stdout()], but with a buffer of `cap` bytes.
+///
+/// A larger buffer means fewer syscalls for very high-throughput output.
+#[inline]
+pub fn buf_stdout_with_capacity(cap: usize) -> BufStdout {
+  BufWriter::with_capacity(cap, io::stdout().lock())
 }
 
 /// Creates a buffered, locked `stderr` writer.
@@ -46,5 +60,184 @@ pub fn buf_stdout() -> BufStdout {
 /// syscalls.
 #[inline]
 pub fn buf_stderr() -> BufStderr {
-  BufWriter::new(io::stderr().lock())
+  buf_stderr_with_capacity(DEFAULT_BUF_CAPACITY)
+}
+
+/// Like [buf_stderr()], but with a buffer of `cap` bytes.
+#[inline]
+pub fn buf_stderr_with_capacity(cap: usize) -> BufStderr {
+  BufWriter::with_capacity(cap, io::stderr().lock())
+}
+
+/// Writes all `bytes` to stdout through [buf_stdout()], then flushes.
+///
+/// The stdout lock is held for the whole write.
+///
+/// ```
+/// use testutils::print_ext::write_stdout_all;
+///
+/// write_stdout_all(b"hello\n")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_stdout_all(bytes: &[u8]) -> io::Result<()> {
+  write_all_and_flush(buf_stdout(), bytes)
+}
+
+/// Writes all `bytes` to stderr through [buf_stderr()], then flushes.
+pub fn write_stderr_all(bytes: &[u8]) -> io::Result<()> {
+  write_all_and_flush(buf_stderr(), bytes)
+}
+
+fn write_all_and_flush<W: Write>(mut writer: W, bytes: &[u8]) -> io::Result<()> {
+  writer.write_all(bytes)?;
+  writer.flush()
+}
+
+/// Like [buf_stdout()], but flushes when the returned guard is dropped.
+///
+/// ```
+/// use std::io::Write;
+///
+/// use testutils::print_ext::buf_lock::buf_stdout_guarded;
+///
+/// let mut out = buf_stdout_guarded();
+/// write!(out, "Continue? [y/N] ")?;
+/// // Flushed here, even without a trailing newline.
+/// drop(out);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[inline]
+pub fn buf_stdout_guarded() -> FlushOnDrop<BufStdout> {
+  buf_stdout().into()
+}
+
+/// Like [buf_stderr()], but flushes when the returned guard is dropped.
+#[inline]
+pub fn buf_stderr_guarded() -> FlushOnDrop<BufStderr> {
+  buf_stderr().into()
+}
+
+/// A writer wrapper that calls [`.flush()`](Write::flush) on `Drop`.
+///
+/// Errors from the final flush are ignored; call `.flush()` explicitly if you
+/// need to handle them.
+#[derive(Debug)]
+pub struct FlushOnDrop<W: Write>(W);
+
+impl<W: Write> FlushOnDrop<W> {
+  pub const fn new(writer: W) -> Self {
+    Self(writer)
+  }
+}
+
+impl<W: Write> From<W> for FlushOnDrop<W> {
+  fn from(writer: W) -> Self {
+    Self::new(writer)
+  }
+}
+
+impl<W: Write> Deref for FlushOnDrop<W> {
+  type Target = W;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl<W: Write> DerefMut for FlushOnDrop<W> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+impl<W: Write> Write for FlushOnDrop<W> {
+  #[inline]
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.write(buf)
+  }
+
+  #[inline]
+  fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+    self.0.write_all(buf)
+  }
+
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> {
+    self.0.flush()
+  }
+}
+
+impl<W: Write> Drop for FlushOnDrop<W> {
+  fn drop(&mut self) {
+    let _ = self.0.flush();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tap::Pipe;
+
+  use super::*;
+
+  /// Only makes the written bytes visible on `flush()`.
+  struct Staged<'a> {
+    pending: Vec<u8>,
+    flushed: &'a mut Vec<u8>,
+  }
+
+  impl Write for Staged<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self
+        .pending
+        .extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      self
+        .flushed
+        .append(&mut self.pending);
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn flush_on_drop() -> io::Result<()> {
+    let mut flushed = Vec::new();
+    let mut guard = FlushOnDrop::new(Staged {
+      pending: Vec::new(),
+      flushed: &mut flushed,
+    });
+
+    write!(guard, "prompt> ")?;
+    assert_eq!(guard.pending, b"prompt> ");
+
+    drop(guard);
+    assert_eq!(flushed, b"prompt> ");
+    Ok(())
+  }
+
+  #[test]
+  fn write_all_delivers_everything() -> io::Result<()> {
+    let payload = (0..16 * 1024)
+      .map(|i| (i % 251) as u8)
+      .collect::<Vec<_>>();
+
+    let mut flushed = Vec::new();
+    Staged {
+      pending: Vec::new(),
+      flushed: &mut flushed,
+    }
+    .pipe(|w| write_all_and_flush(BufWriter::with_capacity(1024, w), &payload))?;
+
+    assert_eq!(flushed, payload);
+    Ok(())
+  }
+
+  #[test]
+  fn with_capacity() {
+    assert_eq!(buf_stdout_with_capacity(64 * 1024).capacity(), 64 * 1024);
+    assert_eq!(buf_stderr_with_capacity(1024).capacity(), 1024);
+    assert_eq!(buf_stdout().capacity(), DEFAULT_BUF_CAPACITY);
+  }
 }
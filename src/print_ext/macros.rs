@@ -48,23 +48,94 @@
 /// 1. Uses `core::any::type_name_of_val` for type information
 /// 2. Formats output as: `{variable_name}: {type} = {debug_representation}`
 /// 3. Multiple arguments generate separate log entries
+///
+/// ## Release builds
+///
+/// The `log::debug!` call is only compiled in when **both** hold for the
+/// testutils crate itself:
+///
+/// - `debug_assertions` is enabled (i.e., the dev/test profile), and
+/// - the `keep_dbg_ref` feature (enabled by default) is on.
+///
+/// Otherwise, `dbg_ref!(x)` is equivalent to `{ let _ = (&x,); }`: the
+/// expression is still evaluated (by reference), but no log call is emitted,
+/// not even a runtime level check.
 #[macro_export]
 macro_rules! dbg_ref {
   ($val:expr $(,)?) => {{
     match &$val {
-      tmp => {
-        log::debug!(
-          "{name}: {type_name} = {tmp:#?}",
-          name = stringify!($val),
-          type_name = core::any::type_name_of_val(tmp),
-        );
-      }
+      tmp => $crate::__dbg_ref_log!(stringify!($val), tmp),
     }
   }};
   ($($val:expr),+ $(,)?) => {
     ($($crate::dbg_ref!($val)),+,)
   };
 }
+
+/// Like [dbg_ref!], but returns the value (by move), like `std::dbg!`.
+///
+/// The value is logged by reference first, with the same format and the same
+/// release-build gating as [dbg_ref!]. So it can be dropped into an
+/// expression or a pipeline.
+///
+/// - `dbg_tap!(a)` => `a`
+/// - `dbg_tap!(a, b)` => `(a, b)`
+///
+/// ## Example
+///
+/// ```
+/// use testutils::dbg_tap;
+///
+/// fn compute() -> Vec<u32> {
+///   (1..=3).collect()
+/// }
+///
+/// let x = dbg_tap!(compute()); // [DEBUG] compute(): alloc::vec::Vec<u32> = [...]
+/// assert_eq!(x, [1, 2, 3]);
+///
+/// let (a, b) = dbg_tap!(x.len(), "ok");
+/// assert_eq!((a, b), (3, "ok"));
+/// ```
+#[macro_export]
+macro_rules! dbg_tap {
+  ($val:expr $(,)?) => {
+    match $val {
+      tmp => {
+        $crate::__dbg_ref_log!(stringify!($val), &tmp);
+        tmp
+      }
+    }
+  };
+  ($($val:expr),+ $(,)?) => {
+    ($($crate::dbg_tap!($val)),+,)
+  };
+}
+
+/// `(name, &value)` => `log::debug!`, used by [dbg_ref!] and [dbg_tap!].
+///
+/// Compiled out without `debug_assertions` or the `keep_dbg_ref` feature.
+#[doc(hidden)]
+#[cfg(all(debug_assertions, feature = "keep_dbg_ref"))]
+#[macro_export]
+macro_rules! __dbg_ref_log {
+  ($name:expr, $tmp:expr) => {
+    log::debug!(
+      "{name}: {type_name} = {tmp:#?}",
+      name = $name,
+      type_name = core::any::type_name_of_val($tmp),
+      tmp = $tmp,
+    )
+  };
+}
+
+#[doc(hidden)]
+#[cfg(not(all(debug_assertions, feature = "keep_dbg_ref")))]
+#[macro_export]
+macro_rules! __dbg_ref_log {
+  ($name:expr, $tmp:expr) => {{
+    let _ = ($tmp,);
+  }};
+}
 // ===========================
 
 /// Outputs the information of the expression(s) to stderr.
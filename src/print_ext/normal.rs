@@ -8,7 +8,11 @@ The goal is to keep call sites terse and consistent when emitting text to
 stdout/stderr.
 */
 
-use core::fmt::{Debug, Display};
+use core::{
+  any::type_name,
+  fmt::{Debug, Display},
+};
+use std::ffi::OsStr;
 
 /// Prints a debug representation of `msg` to **stdout**, followed by a newline.
 ///
@@ -53,3 +57,70 @@ pub fn eputs<T: Display + ?Sized>(msg: &T) {
 pub fn eprint<T: Display + ?Sized>(msg: &T) {
   eprint!("{msg}")
 }
+
+/// Prints `name: type = value` to **stderr**, with the same coloring as
+/// [dbg!](crate::dbg!) (magenta name, yellow type).
+///
+/// Colors are omitted if the `NO_COLOR` env var is set to a non-empty value.
+///
+/// ```
+/// use testutils::print_ext::normal::kv;
+///
+/// kv("width", &30);
+/// // Outputs:
+/// //  width: i32 = 30
+/// ```
+pub fn kv<T: Debug + ?Sized>(name: &str, value: &T) {
+  eprintln!("{}", format_kv(name, value, color_enabled()))
+}
+
+/// Returns `false` if `NO_COLOR` is set and non-empty (see <https://no-color.org>).
+fn color_enabled() -> bool {
+  color_enabled_from(std::env::var_os("NO_COLOR").as_deref())
+}
+
+/// `no_color`: the value of `NO_COLOR`, if set.
+fn color_enabled_from(no_color: Option<&OsStr>) -> bool {
+  no_color.is_none_or(|v| v.is_empty())
+}
+
+fn format_kv<T: Debug + ?Sized>(name: &str, value: &T, color: bool) -> String {
+  let type_name = type_name::<T>();
+  match color {
+    true => {
+      format!(
+        "\u{1B}[35m{name}\u{1B}[0m: \u{1B}[33m{type_name}\u{1B}[0m = {value:?}"
+      )
+    }
+    _ => format!("{name}: {type_name} = {value:?}"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn kv_format() {
+    assert_eq!(
+      format_kv("width", &30, true),
+      "\u{1B}[35mwidth\u{1B}[0m: \u{1B}[33mi32\u{1B}[0m = 30"
+    );
+    assert_eq!(format_kv("label", "size", false), r#"label: str = "size""#);
+    kv("width", &30);
+  }
+
+  #[test]
+  fn kv_respects_no_color() {
+    assert!(color_enabled_from(None));
+    assert!(color_enabled_from(Some(OsStr::new(""))));
+
+    let no_color = color_enabled_from(Some(OsStr::new("1")));
+    assert!(!no_color);
+    assert!(
+      !format_kv("width", &30, no_color)
+        .as_bytes()
+        .contains(&0x1B)
+    );
+  }
+}
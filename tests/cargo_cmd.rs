@@ -1,3 +1,5 @@
+#![cfg(feature = "const_str")]
+
 #[test]
 fn cargo_cmd() {
   use testutils::{
@@ -0,0 +1,53 @@
+//! `dbg_ref!` must not emit any log call without the `keep_dbg_ref` feature.
+//!
+//! Run with:
+//! `cargo test --no-default-features --features std,print_ext --test
+//! dbg_ref_off`
+#![cfg(all(feature = "print_ext", not(feature = "keep_dbg_ref")))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use testutils::{dbg_ref, dbg_tap};
+
+/// Counts every record that reaches the logger.
+struct CountingLogger(AtomicUsize);
+
+impl log::Log for CountingLogger {
+  fn enabled(&self, _: &log::Metadata) -> bool {
+    true
+  }
+
+  fn log(&self, _: &log::Record) {
+    self
+      .0
+      .fetch_add(1, Ordering::SeqCst);
+  }
+
+  fn flush(&self) {}
+}
+
+static LOGGER: CountingLogger = CountingLogger(AtomicUsize::new(0));
+
+#[test]
+fn dbg_ref_is_compiled_out() {
+  log::set_logger(&LOGGER).expect("no other logger in this test binary");
+  log::set_max_level(log::LevelFilter::Trace);
+
+  // Sanity check: the logger itself works.
+  log::debug!("direct");
+  assert_eq!(LOGGER.0.load(Ordering::SeqCst), 1);
+
+  let mut calls = 0;
+  let mut next = || {
+    calls += 1;
+    calls
+  };
+
+  dbg_ref!(next());
+  dbg_ref!(42, "hello");
+  assert_eq!(dbg_tap!(next()), 2);
+
+  // The expression is still evaluated, but nothing is logged.
+  assert_eq!(calls, 2);
+  assert_eq!(LOGGER.0.load(Ordering::SeqCst), 1);
+}
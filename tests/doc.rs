@@ -1,6 +1,8 @@
 //! ```ignore, sh
 //! cargo open-doc
 //! ```
+#![cfg(all(feature = "os_cmd", feature = "const_str"))]
+
 use std::io;
 
 use testutils::{
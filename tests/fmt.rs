@@ -1,3 +1,5 @@
+#![cfg(feature = "os_cmd")]
+
 use std::io;
 
 use testutils::os_cmd::{RunnableCommand, presets::CargoFmt};
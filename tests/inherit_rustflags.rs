@@ -0,0 +1,36 @@
+//! `CargoCmd::with_inherit_rustflags` reads `RUSTFLAGS` from the process
+//! environment, so it lives in its own test binary to avoid racing with unit
+//! tests that check `RUSTFLAGS` is left untouched.
+#![cfg(feature = "os_cmd")]
+
+use testutils::os_cmd::presets::{CargoCmd, cargo_build::flags::RustFlags};
+
+#[test]
+fn appends_to_existing_rustflags() {
+  // SAFETY: This is the only test in this binary.
+  unsafe { std::env::set_var("RUSTFLAGS", "-C foo") };
+
+  let cmd = CargoCmd::default()
+    .with_rust_flags(RustFlags::default().with_crt_static(true.into()));
+
+  let replaced = cmd.clone().rustflags_env();
+  let inherited = cmd
+    .with_inherit_rustflags(true)
+    .into_runner();
+
+  unsafe { std::env::remove_var("RUSTFLAGS") };
+
+  assert_eq!(
+    replaced,
+    Some(("RUSTFLAGS".into(), "-C target-feature=+crt-static".into()))
+  );
+  assert_eq!(
+    inherited.get_envs().as_deref(),
+    Some(
+      &[(
+        "RUSTFLAGS".into(),
+        "-C foo -C target-feature=+crt-static".into()
+      )][..]
+    )
+  );
+}
@@ -1,3 +1,5 @@
+#![cfg(all(feature = "std", feature = "print_ext", feature = "const_str"))]
+
 use testutils::os_cmd::presets::CargoFmt;
 
 pub(crate) fn init_logger() {
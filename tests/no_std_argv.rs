@@ -0,0 +1,40 @@
+//! The argv builders must not depend on `std`.
+//!
+//! To check the library side as well, run:
+//! `cargo test --no-default-features --features presets --test no_std_argv`
+#![no_std]
+#![cfg(feature = "presets")]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use testutils::os_cmd::{
+  MiniStr,
+  presets::{
+    CargoCmd,
+    cargo_build::{BuildStd, RustcTarget},
+  },
+};
+
+#[test]
+fn cargo_cmd_argv_without_std() {
+  let argv: Vec<MiniStr> = CargoCmd::default()
+    .with_nightly(true)
+    .with_target(RustcTarget::thumbv7em_none_eabihf.into())
+    .with_build_std(BuildStd::default().with_core(true))
+    .into_vec();
+
+  assert_eq!(
+    argv,
+    [
+      "cargo",
+      "+nightly",
+      "build",
+      "--profile=release",
+      "--target=thumbv7em-none-eabihf",
+      "-Z",
+      "build-std=core",
+    ]
+  );
+}